@@ -0,0 +1,43 @@
+//! Measures the cost of repeatedly polling a `Delay` which hasn't fired yet.
+//!
+//! Run with `cargo bench --bench poll`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Waker};
+use std::time::{Duration, Instant};
+
+use futures::task::{waker, ArcWake};
+use futures_timer::Delay;
+
+const POLLS: u32 = 1_000_000;
+
+struct Noop;
+
+impl ArcWake for Noop {
+    fn wake_by_ref(_: &Arc<Self>) {}
+}
+
+// Polls a far-future delay `POLLS` times, picking the waker for each poll
+// with `pick`, and returns the average time per poll.
+fn bench(wakers: &[Waker], pick: impl Fn(u32) -> usize) -> Duration {
+    let mut delay = Delay::new(Duration::from_secs(3600));
+    let start = Instant::now();
+    for i in 0..POLLS {
+        let mut cx = Context::from_waker(&wakers[pick(i)]);
+        assert!(Pin::new(&mut delay).poll(&mut cx).is_pending());
+    }
+    start.elapsed() / POLLS
+}
+
+fn main() {
+    let wakers = [waker(Arc::new(Noop)), waker(Arc::new(Noop))];
+    // Warm up the global timer so spawning it isn't measured.
+    bench(&wakers, |_| 0);
+
+    let same = bench(&wakers, |_| 0);
+    let alternating = bench(&wakers, |i| (i % 2) as usize);
+    println!("same waker:        {:?}/poll", same);
+    println!("alternating waker: {:?}/poll", alternating);
+}
@@ -0,0 +1,24 @@
+//! Compares how late 50µs delays fire with and without spinning.
+//!
+//! Run with `cargo bench --bench spin`.
+
+use std::time::Duration;
+
+use futures::executor::block_on;
+use futures_timer::{measure_sleep_accuracy, set_spin_threshold};
+
+const SAMPLES: usize = 1_000;
+
+fn main() {
+    let dur = Duration::from_micros(50);
+    // Warm up the global timer so spawning it isn't measured.
+    block_on(measure_sleep_accuracy(10, dur));
+
+    let parked = block_on(measure_sleep_accuracy(SAMPLES, dur));
+    set_spin_threshold(Duration::from_micros(100));
+    let spun = block_on(measure_sleep_accuracy(SAMPLES, dur));
+    set_spin_threshold(Duration::from_secs(0));
+
+    println!("park only:   {:?}", parked);
+    println!("spin <100µs: {:?}", spun);
+}
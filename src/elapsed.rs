@@ -0,0 +1,59 @@
+//! The error returned when a deadline passes.
+
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when a future did not complete before its deadline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Elapsed(());
+
+impl Elapsed {
+    pub(crate) fn new() -> Elapsed {
+        Elapsed(())
+    }
+}
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl Error for Elapsed {}
+
+/// Error returned by `FutureExt::timeout_diagnostic` when a future did not
+/// complete before its deadline.
+///
+/// Unlike `Elapsed` this records how far the inner future got, which helps
+/// tell a future that was slow apart from one that was never driven.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ElapsedDiagnostic {
+    inner_poll_count: u64,
+}
+
+impl ElapsedDiagnostic {
+    pub(crate) fn new(inner_poll_count: u64) -> ElapsedDiagnostic {
+        ElapsedDiagnostic { inner_poll_count }
+    }
+
+    /// Returns how many times the inner future was polled before the
+    /// deadline passed.
+    ///
+    /// Zero means the inner future was never polled at all, which usually
+    /// points at a starved executor rather than a slow future.
+    pub fn inner_poll_count(&self) -> u64 {
+        self.inner_poll_count
+    }
+}
+
+impl fmt::Display for ElapsedDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "deadline has elapsed after polling the future {} times",
+            self.inner_poll_count
+        )
+    }
+}
+
+impl Error for ElapsedDiagnostic {}
@@ -0,0 +1,353 @@
+//! Extension methods for futures.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+use std::time::Instant;
+
+#[cfg(all(
+    feature = "futures-core",
+    not(all(target_arch = "wasm32", feature = "wasm-bindgen"))
+))]
+use futures_core::future::FusedFuture;
+
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+use crate::TimerHandle;
+use crate::{Delay, Elapsed, ElapsedDiagnostic};
+
+/// An extension trait for futures which provides convenient accessors for
+/// timing-related methods.
+pub trait FutureExt: Future {
+    /// Requires this future to complete within `dur`.
+    ///
+    /// The returned future resolves to `Ok` with the output of this future if
+    /// it completes in time, or to `Err(Elapsed)` otherwise. This future is
+    /// always polled before checking the deadline, and the deadline is counted
+    /// from when `timeout` is called.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use futures_timer::{Delay, FutureExt};
+    ///
+    /// let slow = Delay::new(Duration::from_secs(10));
+    /// let res = slow.timeout(Duration::from_secs(1)).await;
+    /// assert!(res.is_err());
+    /// # }
+    /// ```
+    fn timeout(self, dur: Duration) -> Timeout<Self>
+    where
+        Self: Sized,
+    {
+        Timeout {
+            future: self,
+            delay: Delay::new(dur),
+            completed: false,
+            elapsed: false,
+            due: false,
+        }
+    }
+
+    /// Requires this future to complete before the instant `at`.
+    ///
+    /// This is the same as `timeout` except that the deadline is given as an
+    /// absolute instant rather than relative to now.
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    fn timeout_at(self, at: Instant) -> Timeout<Self>
+    where
+        Self: Sized,
+    {
+        Timeout {
+            future: self,
+            delay: Delay::new_at(at),
+            completed: false,
+            elapsed: false,
+            due: false,
+        }
+    }
+
+    /// Requires this future to complete within `dur`, as measured by the timer
+    /// behind `handle`.
+    ///
+    /// This is the same as `timeout` except that the deadline is registered
+    /// with the given timer instead of the default one.
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    fn timeout_with(self, dur: Duration, handle: TimerHandle) -> Timeout<Self>
+    where
+        Self: Sized,
+    {
+        Timeout {
+            future: self,
+            delay: Delay::new_handle(dur, handle),
+            completed: false,
+            elapsed: false,
+            due: false,
+        }
+    }
+
+    /// Requires this future to complete within the default timeout of the
+    /// timer behind `handle`.
+    ///
+    /// This is the same as `timeout_with` using the duration set with
+    /// `TimerHandle::set_default_timeout`, read when this is called, which
+    /// keeps timeout policy in one place rather than at every call site.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no default timeout has been set for the timer.
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    fn timeout_default(self, handle: TimerHandle) -> Timeout<Self>
+    where
+        Self: Sized,
+    {
+        let dur = handle
+            .default_timeout()
+            .expect("no default timeout set for this timer");
+        self.timeout_with(dur, handle)
+    }
+
+    /// Requires this future to complete within `dur`, reporting how many
+    /// times it was polled if it doesn't.
+    ///
+    /// This is the same as `timeout` except that the error also records the
+    /// number of times this future was polled, see
+    /// `ElapsedDiagnostic::inner_poll_count`. To make a count of zero mean
+    /// that this future was never polled before the deadline, the deadline is
+    /// checked before polling this future rather than after, so a future which
+    /// would have been ready on the poll that sees the deadline still times
+    /// out.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use futures_timer::{Delay, FutureExt};
+    ///
+    /// let slow = Delay::new(Duration::from_secs(10));
+    /// let err = slow.timeout_diagnostic(Duration::from_secs(1)).await.unwrap_err();
+    /// assert!(err.inner_poll_count() > 0);
+    /// # }
+    /// ```
+    fn timeout_diagnostic(self, dur: Duration) -> TimeoutDiagnostic<Self>
+    where
+        Self: Sized,
+    {
+        TimeoutDiagnostic {
+            future: self,
+            delay: Delay::new(dur),
+            polls: 0,
+        }
+    }
+
+    /// Makes this future take at least `dur` to complete.
+    ///
+    /// The returned future resolves to the output of this future, but if this
+    /// future completes before `dur` has elapsed since `at_least` was called
+    /// then the output is held back until `dur` has elapsed. If this future
+    /// takes longer than `dur` its output is yielded right away.
+    ///
+    /// This can be used to normalize response times, for example to avoid
+    /// leaking through timing whether an authentication check failed early.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use futures_timer::FutureExt;
+    ///
+    /// let answer = async { 42 }.at_least(Duration::from_millis(100)).await;
+    /// assert_eq!(answer, 42);
+    /// # }
+    /// ```
+    fn at_least(self, dur: Duration) -> AtLeast<Self>
+    where
+        Self: Sized,
+    {
+        AtLeast {
+            future: self,
+            output: None,
+            done: false,
+            delay: Delay::new(dur),
+        }
+    }
+}
+
+impl<F: Future + ?Sized> FutureExt for F {}
+
+/// Future returned by `FutureExt::at_least`.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct AtLeast<F: Future> {
+    future: F,
+    output: Option<F::Output>,
+    done: bool,
+    delay: Delay,
+}
+
+impl<F: Future> Future for AtLeast<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `future` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        if !this.done {
+            let future = unsafe { Pin::new_unchecked(&mut this.future) };
+            match future.poll(cx) {
+                Poll::Ready(output) => {
+                    this.output = Some(output);
+                    this.done = true;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        match Pin::new(&mut this.delay).poll(cx) {
+            Poll::Ready(()) => {
+                let output = this.output.take();
+                Poll::Ready(output.expect("`AtLeast` polled after completion"))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Future returned by `FutureExt::timeout`, `FutureExt::timeout_at` and
+/// `FutureExt::timeout_with`.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct Timeout<F> {
+    future: F,
+    delay: Delay,
+    completed: bool,
+    elapsed: bool,
+    // Set by `set_remaining` when the new deadline has already passed, as the
+    // timer may not have processed it by the next poll.
+    due: bool,
+}
+
+impl<F> Timeout<F> {
+    /// Returns the delay tracking this timeout's deadline.
+    ///
+    /// This can be used to inspect the deadline, for example with
+    /// `Delay::remaining` to find out how much of the budget is left.
+    pub fn delay(&self) -> &Delay {
+        &self.delay
+    }
+
+    /// Resets the deadline of this timeout to `dur` from now.
+    ///
+    /// This re-arms the timeout even if it has already elapsed, so the inner
+    /// future, whose state is kept intact, can be driven further.
+    pub fn reset(self: Pin<&mut Self>, dur: Duration) {
+        // Safety: `delay` is not structurally pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        this.elapsed = false;
+        this.due = false;
+        this.delay.rearm(dur)
+    }
+
+    /// Resets the deadline of this timeout to the instant `at`.
+    ///
+    /// Like `reset`, this re-arms the timeout even if it has already elapsed.
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    pub fn reset_at(self: Pin<&mut Self>, at: Instant) {
+        // Safety: `delay` is not structurally pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        this.elapsed = false;
+        this.due = false;
+        this.delay.rearm_at(at)
+    }
+
+    /// Shortens this timeout to elapse within `dur`, unless it's already set
+    /// to elapse sooner than that.
+    ///
+    /// This is the alternative to nesting timeouts, as in
+    /// `future.timeout(a).timeout(b)`: the result keeps only the tighter of
+    /// the two deadlines and a single `Delay`, rather than registering both
+    /// with the timer.
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    pub fn tighten(mut self, dur: Duration) -> Timeout<F> {
+        let at = Instant::now() + dur;
+        if at < self.delay.deadline() {
+            self.delay.rearm_at(at);
+        }
+        self
+    }
+
+    /// Sets the time left before this timeout elapses to `remaining`.
+    ///
+    /// This is the same as `reset`, but is meant for sharing one overall
+    /// budget across several phases: compute what is left of the budget,
+    /// for example from `self.delay().remaining()` minus the time spent on
+    /// setup, and carry on with that. A `remaining` of zero makes the timeout
+    /// elapse on the next poll, unless the inner future is ready by then.
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    pub fn set_remaining(mut self: Pin<&mut Self>, remaining: Duration) {
+        self.as_mut().reset_at(Instant::now() + remaining);
+        // Safety: `due` is not structurally pinned.
+        unsafe { self.get_unchecked_mut() }.due = remaining == Duration::from_secs(0);
+    }
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `future` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+        if let Poll::Ready(output) = future.poll(cx) {
+            this.completed = true;
+            return Poll::Ready(Ok(output));
+        }
+        if !this.due && Pin::new(&mut this.delay).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+        this.elapsed = true;
+        Poll::Ready(Err(Elapsed::new()))
+    }
+}
+
+#[cfg(all(
+    feature = "futures-core",
+    not(all(target_arch = "wasm32", feature = "wasm-bindgen"))
+))]
+impl<F: Future> FusedFuture for Timeout<F> {
+    fn is_terminated(&self) -> bool {
+        self.completed || self.elapsed
+    }
+}
+
+/// Future returned by `FutureExt::timeout_diagnostic`.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct TimeoutDiagnostic<F> {
+    future: F,
+    delay: Delay,
+    polls: u64,
+}
+
+impl<F: Future> Future for TimeoutDiagnostic<F> {
+    type Output = Result<F::Output, ElapsedDiagnostic>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `future` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        if Pin::new(&mut this.delay).poll(cx).is_ready() {
+            return Poll::Ready(Err(ElapsedDiagnostic::new(this.polls)));
+        }
+
+        this.polls += 1;
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+        future.poll(cx).map(Ok)
+    }
+}
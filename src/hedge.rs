@@ -0,0 +1,147 @@
+//! Hedged requests: racing extra attempts against a slow one.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::Delay;
+
+/// Future for the `hedge` function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Hedge<M, Fut: Future> {
+    make: M,
+    attempts: Vec<Pin<Box<Fut>>>,
+    started: usize,
+    max_attempts: usize,
+    hedge_after: Duration,
+    delay: Option<Delay>,
+    last_error: Option<Fut::Output>,
+}
+
+/// Runs attempts created by `make`, starting another one whenever the
+/// attempts in flight have been running for `hedge_after` without succeeding.
+///
+/// At most `max_attempts` attempts are started, and at least one always is.
+/// All attempts in flight are raced against each other and the returned future
+/// resolves with the first `Ok` produced, dropping all other attempts. An
+/// attempt which fails doesn't end the race, instead the next attempt (if
+/// any) is started right away, even if other attempts are still in flight.
+/// If every attempt fails the error of the last one to fail is returned.
+///
+/// Nothing is started until the returned future is first polled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[async_std::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use futures_timer::hedge;
+///
+/// async fn fetch() -> Result<u32, ()> {
+///     // ...
+/// #   Ok(1)
+/// }
+///
+/// let value = hedge(fetch, Duration::from_millis(50), 3).await;
+/// # }
+/// ```
+pub fn hedge<M, Fut, T, E>(make: M, hedge_after: Duration, max_attempts: usize) -> Hedge<M, Fut>
+where
+    M: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    Hedge {
+        make,
+        attempts: Vec::new(),
+        started: 0,
+        max_attempts: max_attempts.max(1),
+        hedge_after,
+        delay: None,
+        last_error: None,
+    }
+}
+
+impl<M, Fut> Hedge<M, Fut>
+where
+    M: FnMut() -> Fut,
+    Fut: Future,
+{
+    fn start(&mut self) {
+        self.attempts.push(Box::pin((self.make)()));
+        self.started += 1;
+        match &mut self.delay {
+            Some(delay) => delay.rearm(self.hedge_after),
+            None => self.delay = Some(Delay::new(self.hedge_after)),
+        }
+    }
+}
+
+impl<M, Fut: Future> Unpin for Hedge<M, Fut> {}
+
+impl<M, Fut, T, E> Future for Hedge<M, Fut>
+where
+    M: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if this.started == 0 {
+            this.start();
+        }
+        loop {
+            let mut failed = 0;
+            let mut i = 0;
+            while i < this.attempts.len() {
+                match this.attempts[i].as_mut().poll(cx) {
+                    Poll::Ready(Ok(value)) => {
+                        this.attempts.clear();
+                        return Poll::Ready(Ok(value));
+                    }
+                    Poll::Ready(Err(e)) => {
+                        this.attempts.swap_remove(i);
+                        this.last_error = Some(Err(e));
+                        failed += 1;
+                    }
+                    Poll::Pending => i += 1,
+                }
+            }
+
+            if this.started == this.max_attempts {
+                if !this.attempts.is_empty() {
+                    return Poll::Pending;
+                }
+                let err = this.last_error.take();
+                return Poll::Ready(err.expect("`Hedge` polled after completion"));
+            }
+
+            // Start a new attempt for each one which has just failed, or one
+            // if we've waited long enough on the ones still running.
+            if failed == 0 {
+                let delay = this.delay.as_mut().expect("started attempts have a delay");
+                if Pin::new(delay).poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                failed = 1;
+            }
+            for _ in 0..failed.min(this.max_attempts - this.started) {
+                this.start();
+            }
+        }
+    }
+}
+
+impl<M, Fut: Future> fmt::Debug for Hedge<M, Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hedge")
+            .field("in_flight", &self.attempts.len())
+            .field("started", &self.started)
+            .field("max_attempts", &self.max_attempts)
+            .field("hedge_after", &self.hedge_after)
+            .finish()
+    }
+}
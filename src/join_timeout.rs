@@ -0,0 +1,127 @@
+//! Joining two futures under a shared deadline.
+
+use std::fmt;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::Delay;
+
+/// Future for the `join_timeout` function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct JoinTimeout<A: Future, B: Future> {
+    a: MaybeDone<A>,
+    b: MaybeDone<B>,
+    delay: Delay,
+}
+
+enum MaybeDone<F: Future> {
+    Pending(F),
+    Done(F::Output),
+    Gone,
+}
+
+/// Drives `a` and `b` concurrently until both complete or `dur` elapses.
+///
+/// The returned future resolves to the output of each future, or `None` for a
+/// future which had not completed by the time the deadline passed. Futures
+/// which did not complete are dropped. If both futures complete before the
+/// deadline the result is returned right away.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[async_std::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use futures_timer::{join_timeout, Delay};
+///
+/// let fast = async { 1 };
+/// let slow = async {
+///     Delay::new(Duration::from_secs(10)).await;
+///     2
+/// };
+/// let (a, b) = join_timeout(fast, slow, Duration::from_secs(1)).await;
+/// assert_eq!(a, Some(1));
+/// assert_eq!(b, None);
+/// # }
+/// ```
+pub fn join_timeout<A, B>(a: A, b: B, dur: Duration) -> JoinTimeout<A, B>
+where
+    A: Future,
+    B: Future,
+{
+    JoinTimeout {
+        a: MaybeDone::Pending(a),
+        b: MaybeDone::Pending(b),
+        delay: Delay::new(dur),
+    }
+}
+
+impl<F: Future> MaybeDone<F> {
+    /// Polls the inner future if it's still pending, returning whether it has
+    /// completed.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        // Safety: the future in `Pending` is never moved out of `self`, it's
+        // only ever dropped in place when overwritten.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            let output = match this {
+                MaybeDone::Pending(f) => match Pin::new_unchecked(f).poll(cx) {
+                    Poll::Ready(output) => output,
+                    Poll::Pending => return false,
+                },
+                MaybeDone::Done(_) => return true,
+                MaybeDone::Gone => panic!("JoinTimeout polled after completion"),
+            };
+            *this = MaybeDone::Done(output);
+            true
+        }
+    }
+
+    fn take(self: Pin<&mut Self>) -> Option<F::Output> {
+        // Safety: a pending future is dropped in place here rather than moved,
+        // and the output of a finished future is not pinned.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            match this {
+                MaybeDone::Done(_) => match mem::replace(this, MaybeDone::Gone) {
+                    MaybeDone::Done(output) => Some(output),
+                    _ => unreachable!(),
+                },
+                _ => {
+                    *this = MaybeDone::Gone;
+                    None
+                }
+            }
+        }
+    }
+}
+
+impl<A: Future, B: Future> Future for JoinTimeout<A, B> {
+    type Output = (Option<A::Output>, Option<B::Output>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `a` and `b` are structurally pinned and `delay` is `Unpin`.
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut a = unsafe { Pin::new_unchecked(&mut this.a) };
+        let mut b = unsafe { Pin::new_unchecked(&mut this.b) };
+
+        let a_done = a.as_mut().poll(cx);
+        let b_done = b.as_mut().poll(cx);
+        if !(a_done && b_done) && Pin::new(&mut this.delay).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+        Poll::Ready((a.take(), b.take()))
+    }
+}
+
+impl<A: Future, B: Future> fmt::Debug for JoinTimeout<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinTimeout")
+            .field("delay", &self.delay)
+            .finish()
+    }
+}
@@ -13,6 +13,14 @@
 //! # }
 //! ```
 
+//!
+//! # Features
+//!
+//! The default `futures-core` feature implements `Stream` for `Interval` and
+//! `FusedFuture` for `Delay` and `Timeout`, and provides the `StreamExt`
+//! adapters. Without it the crate has no dependencies beyond `std` on native
+//! targets.
+
 #![deny(missing_docs)]
 #![warn(missing_debug_implementations)]
 
@@ -21,7 +29,43 @@ mod native;
 #[cfg(all(target_arch = "wasm32", feature = "wasm-bindgen"))]
 mod wasm;
 
+mod elapsed;
+mod ext;
+mod hedge;
+mod join_timeout;
+mod poll_delay;
+mod sleep;
+#[cfg(feature = "futures-core")]
+mod stream_ext;
+
 #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
 pub use self::native::Delay;
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+pub use self::native::{
+    measure_sleep_accuracy, reset_global, set_min_wake_interval, set_spin_threshold,
+    shutdown_global, Budget, DelayTrigger, DelayWaiter, Guard, Interval, Jittered, SleepAccuracy,
+    Spaced, Timer, TimerHandle, WithMissCount,
+};
+#[cfg(all(
+    feature = "metrics",
+    not(all(target_arch = "wasm32", feature = "wasm-bindgen"))
+))]
+pub use self::native::{DeadlineDistribution, TimerStats};
 #[cfg(all(target_arch = "wasm32", feature = "wasm-bindgen"))]
 pub use self::wasm::Delay;
+
+pub use self::elapsed::{Elapsed, ElapsedDiagnostic};
+pub use self::ext::{AtLeast, FutureExt, Timeout, TimeoutDiagnostic};
+pub use self::hedge::{hedge, Hedge};
+pub use self::join_timeout::{join_timeout, JoinTimeout};
+pub use self::poll_delay::{poll_delay, PollDelay};
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+pub use self::sleep::sleep_until;
+pub use self::sleep::{sleep, timeout, timeout_pinned};
+#[cfg(feature = "futures-core")]
+pub use self::stream_ext::{Debounce, StreamExt, TakeUntilDelay, Throttle};
+#[cfg(all(
+    feature = "futures-core",
+    not(all(target_arch = "wasm32", feature = "wasm-bindgen"))
+))]
+pub use self::stream_ext::{Windowed, WithIntervals};
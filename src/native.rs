@@ -1,15 +1,28 @@
+mod accuracy;
 mod arc_list;
 mod atomic_waker;
+mod budget;
 mod delay;
 mod global;
 mod heap;
 mod heap_timer;
+mod interval;
+mod rng;
+#[cfg(feature = "metrics")]
+mod stats;
 mod timer;
 
 use self::arc_list::{ArcList, Node};
 use self::atomic_waker::AtomicWaker;
 use self::heap::{Heap, Slot};
 use self::heap_timer::HeapTimer;
-use self::timer::{ScheduledTimer, Timer, TimerHandle};
+use self::timer::ScheduledTimer;
 
-pub use self::delay::Delay;
+pub use self::accuracy::{measure_sleep_accuracy, SleepAccuracy};
+pub use self::budget::{Budget, Guard};
+pub use self::delay::{Delay, DelayTrigger, DelayWaiter};
+pub use self::global::{reset_global, set_min_wake_interval, set_spin_threshold, shutdown_global};
+pub use self::interval::{Interval, Jittered, Spaced, WithMissCount};
+#[cfg(feature = "metrics")]
+pub use self::stats::{DeadlineDistribution, TimerStats};
+pub use self::timer::{Timer, TimerHandle};
@@ -0,0 +1,58 @@
+//! Runtime measurement of how precisely `Delay` fires on this machine.
+
+use std::time::{Duration, Instant};
+
+use super::Delay;
+
+/// Lateness statistics collected by `measure_sleep_accuracy`.
+///
+/// Each value is how long after its requested duration a `Delay` actually
+/// completed, as observed by the task awaiting it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SleepAccuracy {
+    /// Smallest observed lateness.
+    pub min: Duration,
+    /// Average observed lateness.
+    pub mean: Duration,
+    /// Largest observed lateness.
+    pub max: Duration,
+}
+
+/// Measures how late `Delay`s of `dur` fire on the current machine.
+///
+/// This awaits `samples` delays of `dur` one after another on the default
+/// timer, recording how much later than `dur` each one completed. The result
+/// includes the latency of the helper thread as well as that of the executor
+/// running this future, which is what callers typically want to know when
+/// deciding whether short sleeps are precise enough for their purposes.
+///
+/// This is a diagnostic tool and is expected to take roughly
+/// `samples * dur` to complete. If `samples` is zero all statistics are zero.
+pub async fn measure_sleep_accuracy(samples: usize, dur: Duration) -> SleepAccuracy {
+    let zero = Duration::from_secs(0);
+    if samples == 0 {
+        return SleepAccuracy {
+            min: zero,
+            mean: zero,
+            max: zero,
+        };
+    }
+
+    let mut min = None;
+    let mut max = zero;
+    let mut total = 0u128;
+    for _ in 0..samples {
+        let start = Instant::now();
+        Delay::new(dur).await;
+        let late = start.elapsed().checked_sub(dur).unwrap_or(zero);
+        min = Some(min.map_or(late, |min: Duration| min.min(late)));
+        max = max.max(late);
+        total += late.as_nanos();
+    }
+
+    SleepAccuracy {
+        min: min.unwrap_or(zero),
+        mean: Duration::from_nanos((total / samples as u128) as u64),
+        max,
+    }
+}
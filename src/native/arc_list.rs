@@ -50,6 +50,12 @@ impl<T> ArcList<T> {
         }
     }
 
+    /// Returns whether this list currently has no entries.
+    pub fn is_empty(&self) -> bool {
+        let head = self.list.load(SeqCst);
+        head == 0 || head == 1
+    }
+
     /// Atomically empties this list, returning a new owned copy which can be
     /// used to iterate over the entries.
     pub fn take(&self) -> ArcList<T> {
@@ -96,7 +102,7 @@ impl<T> ArcList<T> {
 
 impl<T> Drop for ArcList<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop() {
+        while self.pop().is_some() {
             // ...
         }
     }
@@ -41,10 +41,6 @@ const WAKING: usize = 0b10;
 impl AtomicWaker {
     /// Create an `AtomicWaker`.
     pub fn new() -> AtomicWaker {
-        // Make sure that task is Sync
-        trait AssertSync: Sync {}
-        impl AssertSync for Waker {}
-
         AtomicWaker {
             state: AtomicUsize::new(WAITING),
             waker: UnsafeCell::new(None),
@@ -104,11 +100,20 @@ impl AtomicWaker {
     /// }
     /// ```
     pub fn register(&self, waker: &Waker) {
-        match self.state.compare_and_swap(WAITING, REGISTERING, Acquire) {
+        match self
+            .state
+            .compare_exchange(WAITING, REGISTERING, Acquire, Acquire)
+            .unwrap_or_else(|x| x)
+        {
             WAITING => {
                 unsafe {
-                    // Locked acquired, update the waker cell
-                    *self.waker.get() = Some(waker.clone());
+                    // Locked acquired, update the waker cell. If the stored
+                    // waker would already wake the same task, keep it and
+                    // skip the clone.
+                    match &*self.waker.get() {
+                        Some(old) if old.will_wake(waker) => {}
+                        _ => *self.waker.get() = Some(waker.clone()),
+                    }
 
                     // Release the lock. If the state transitioned to include
                     // the `WAKING` bit, this means that a wake has been
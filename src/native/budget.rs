@@ -0,0 +1,119 @@
+//! A time budget shared by a sequence of futures.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use super::Delay;
+use crate::Elapsed;
+
+/// A total amount of time to be spent across a number of steps.
+///
+/// Each step is run through `Budget::guard`, which fails the step if it runs
+/// past what's left of the budget and otherwise deducts the time it took.
+/// Once the budget is exhausted every subsequent step fails immediately.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[async_std::main]
+/// # async fn main() -> Result<(), futures_timer::Elapsed> {
+/// use std::time::Duration;
+/// use futures_timer::{Budget, Delay};
+///
+/// let mut budget = Budget::new(Duration::from_secs(5));
+/// budget.guard(Delay::new(Duration::from_secs(1))).await?;
+/// budget.guard(Delay::new(Duration::from_secs(1))).await?;
+/// assert!(budget.remaining() <= Duration::from_secs(3));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Budget {
+    remaining: Duration,
+}
+
+impl Budget {
+    /// Creates a new budget of `total` time.
+    pub fn new(total: Duration) -> Budget {
+        Budget { remaining: total }
+    }
+
+    /// Returns how much of the budget is left.
+    pub fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    /// Runs `future` against whatever is left of this budget.
+    ///
+    /// The returned future resolves to the output of `future`, or to
+    /// `Err(Elapsed)` if the budget runs out first, in which case the budget
+    /// is left exhausted. The time between calling this method and the
+    /// returned future completing (or being dropped) is deducted from the
+    /// budget.
+    pub fn guard<F: Future>(&mut self, future: F) -> Guard<'_, F> {
+        Guard {
+            delay: Delay::new(self.remaining),
+            start: Instant::now(),
+            budget: self,
+            future,
+            done: false,
+        }
+    }
+
+    fn deduct(&mut self, start: Instant) {
+        self.remaining = self
+            .remaining
+            .checked_sub(start.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(0));
+    }
+}
+
+/// Future returned by `Budget::guard`.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct Guard<'a, F> {
+    budget: &'a mut Budget,
+    future: F,
+    delay: Delay,
+    start: Instant,
+    done: bool,
+}
+
+impl<F: Future> Future for Guard<'_, F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `future` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.done {
+            panic!("`Guard` polled after completion");
+        }
+
+        let exhausted = this.budget.remaining == Duration::from_secs(0);
+        if !exhausted {
+            let future = unsafe { Pin::new_unchecked(&mut this.future) };
+            if let Poll::Ready(output) = future.poll(cx) {
+                this.done = true;
+                this.budget.deduct(this.start);
+                return Poll::Ready(Ok(output));
+            }
+            if Pin::new(&mut this.delay).poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+
+        this.done = true;
+        this.budget.remaining = Duration::from_secs(0);
+        Poll::Ready(Err(Elapsed::new()))
+    }
+}
+
+impl<F> Drop for Guard<'_, F> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.budget.deduct(self.start);
+        }
+    }
+}
@@ -5,14 +5,20 @@
 
 use std::fmt;
 use std::future::Future;
+use std::io;
+use std::ops::Range;
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "futures-core")]
+use futures_core::future::FusedFuture;
 
 use super::arc_list::Node;
+use super::rng;
 use super::AtomicWaker;
 use super::{ScheduledTimer, TimerHandle};
 
@@ -25,6 +31,10 @@ use super::{ScheduledTimer, TimerHandle};
 /// at.
 pub struct Delay {
     state: Option<Arc<Node<ScheduledTimer>>>,
+    deadline: Instant,
+
+    // Whether `poll` has returned `Ready` since the last reset.
+    terminated: bool,
 }
 
 impl Delay {
@@ -34,17 +44,129 @@ impl Delay {
     /// The default timer will be spun up in a helper thread on first use.
     #[inline]
     pub fn new(dur: Duration) -> Delay {
-        Delay::new_handle(Instant::now() + dur, Default::default())
+        Delay::new_handle(dur, Default::default())
+    }
+
+    /// Creates a new future which will fire at the time specified by `at`.
+    ///
+    /// The returned object will be bound to the default timer for this thread.
+    /// The default timer will be spun up in a helper thread on first use.
+    #[inline]
+    pub fn new_at(at: Instant) -> Delay {
+        Delay::new_at_handle(at, Default::default())
+    }
+
+    /// Creates a new future which will fire at `dur` time into the future,
+    /// failing if the default timer isn't available.
+    ///
+    /// This is the same as `Delay::new` except that instead of returning a
+    /// delay which panics when polled it returns an error if the global
+    /// helper thread couldn't be spawned or has been stopped with
+    /// `shutdown_global`.
+    pub fn try_new(dur: Duration) -> io::Result<Delay> {
+        let handle = TimerHandle::try_default()?;
+        if handle.inner.upgrade().is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "global timer has been shut down",
+            ));
+        }
+        Ok(Delay::new_handle(dur, handle))
+    }
+
+    /// Creates a new future which will fire at `dur` time into the future.
+    ///
+    /// The returned instance of `Delay` will be bound to the timer specified by
+    /// the `handle` argument. It never touches the default timer, so with a
+    /// manually driven `Timer` it only fires once that timer is advanced.
+    pub fn new_handle(dur: Duration, handle: TimerHandle) -> Delay {
+        #[cfg(feature = "metrics")]
+        super::TimerStats::global().record_deadline(dur);
+        Delay::register(Instant::now() + dur, handle)
     }
 
     /// Creates a new future which will fire at the time specified by `at`.
     ///
     /// The returned instance of `Delay` will be bound to the timer specified by
     /// the `handle` argument.
-    pub(crate) fn new_handle(at: Instant, handle: TimerHandle) -> Delay {
+    pub fn new_at_handle(at: Instant, handle: TimerHandle) -> Delay {
+        #[cfg(feature = "metrics")]
+        super::TimerStats::global().record_deadline(at.saturating_duration_since(Instant::now()));
+        Delay::register(at, handle)
+    }
+
+    /// Creates a new future which will fire after a random duration within
+    /// `range`.
+    ///
+    /// The duration is picked by a small non-cryptographic generator local to
+    /// the current thread, which is intended for simulating variable latency in
+    /// tests. It is seeded differently for each thread unless
+    /// `Delay::seed_in_range` is called, after which the sequence of durations
+    /// picked on this thread is reproducible. If `range` is empty the delay
+    /// fires after `range.start`.
+    pub fn new_in_range(range: Range<Duration>) -> Delay {
+        Delay::new(rng::in_range(range))
+    }
+
+    /// Seeds the generator used by `Delay::new_in_range` on the current
+    /// thread.
+    pub fn seed_in_range(seed: u64) {
+        rng::seed(seed)
+    }
+
+    /// Creates a new future which will fire at the next occurrence of the
+    /// given wall-clock time of day.
+    ///
+    /// The time of day is interpreted in the timezone `tz_offset` seconds east
+    /// of UTC. If that time has already passed today the delay fires at that
+    /// time tomorrow.
+    ///
+    /// The deadline is computed once from `SystemTime::now` and then tracked
+    /// with the monotonic clock like any other `Delay`. This means the offset
+    /// is taken as fixed: if a daylight saving transition happens before the
+    /// deadline the delay still fires at the instant computed with the given
+    /// offset, which may be an hour off the local wall-clock time. Adjustments
+    /// to the system clock after this is called are likewise not tracked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hour`, `minute`, or `second` are out of range.
+    pub fn at_time_of_day(hour: u32, minute: u32, second: u32, tz_offset: i32) -> Delay {
+        assert!(hour < 24, "hour out of range");
+        assert!(minute < 60, "minute out of range");
+        assert!(second < 60, "second out of range");
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0));
+        let target = hour * 3600 + minute * 60 + second;
+        Delay::new(until_time_of_day(since_epoch, tz_offset, target))
+    }
+
+    /// Creates a new future which will fire `dur` after the deadline of
+    /// `other`.
+    ///
+    /// The deadline of `other` is read once when this is called, so later
+    /// calls to `reset` on `other` do not affect the returned delay. If `other`
+    /// has already fired its original deadline is still used, meaning the
+    /// returned delay may already be in the past and fire promptly.
+    ///
+    /// The returned delay is bound to the same timer as `other`.
+    pub fn new_after(other: &Delay, dur: Duration) -> Delay {
+        let at = other.deadline + dur;
+        let handle = match other.state {
+            Some(ref state) => TimerHandle {
+                inner: state.inner.clone(),
+            },
+            None => return Delay::inert(at),
+        };
+        Delay::new_at_handle(at, handle)
+    }
+
+    /// Registers a new delay firing at `at` with the timer behind `handle`.
+    fn register(at: Instant, handle: TimerHandle) -> Delay {
         let inner = match handle.inner.upgrade() {
             Some(i) => i,
-            None => return Delay { state: None },
+            None => return Delay::inert(at),
         };
         let state = Arc::new(Node::new(ScheduledTimer {
             at: Mutex::new(Some(at)),
@@ -52,29 +174,95 @@ impl Delay {
             waker: AtomicWaker::new(),
             inner: handle.inner,
             slot: Mutex::new(None),
+            #[cfg(feature = "metrics")]
+            resets: AtomicUsize::new(0),
         }));
 
         // If we fail to actually push our node then we've become an inert
         // timer, meaning that we'll want to immediately return an error from
         // `poll`.
         if inner.list.push(&state).is_err() {
-            return Delay { state: None };
+            return Delay::inert(at);
         }
 
-        inner.waker.wake();
-        Delay { state: Some(state) }
+        if inner.needs_wake(at) {
+            inner.waker.wake();
+        }
+        Delay {
+            state: Some(state),
+            deadline: at,
+            terminated: false,
+        }
     }
 
-    /// Resets this timeout to an new timeout which will fire at the time
-    /// specified by `at`.
+    /// Creates a delay which isn't bound to any timer and panics when polled.
+    fn inert(deadline: Instant) -> Delay {
+        Delay {
+            state: None,
+            deadline,
+            terminated: false,
+        }
+    }
+
+    /// Returns the instant at which this delay is scheduled to fire.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns how long until this delay's deadline, or zero if it has already
+    /// passed.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns whether this delay's deadline has passed.
+    ///
+    /// This only compares the deadline against `Instant::now()`, it doesn't
+    /// register for a wakeup or otherwise affect subsequent calls to `poll`.
+    /// Note that the timer may not have processed the deadline yet, so a poll
+    /// right after this returns `true` may still briefly return `Pending`.
+    pub fn is_elapsed(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// Resets this timeout to an new timeout which will fire `dur` from now.
     #[inline]
     pub fn reset(&mut self, dur: Duration) {
-        if self._reset(dur).is_err() {
+        self.reset_at(Instant::now() + dur)
+    }
+
+    /// Resets this timeout to an new timeout which will fire at the time
+    /// specified by `at`.
+    pub fn reset_at(&mut self, at: Instant) {
+        self.reset_at_counted(at, true)
+    }
+
+    /// Resets this delay like `reset`, without counting towards the warning
+    /// about delays being reset over and over without firing.
+    ///
+    /// This is for this crate's own adapters, such as `Debounce` and
+    /// `Interval`, for which that is the expected usage.
+    pub(crate) fn rearm(&mut self, dur: Duration) {
+        self.rearm_at(Instant::now() + dur)
+    }
+
+    /// Resets this delay like `reset_at`, see `Delay::rearm`.
+    pub(crate) fn rearm_at(&mut self, at: Instant) {
+        self.reset_at_counted(at, false)
+    }
+
+    fn reset_at_counted(&mut self, at: Instant, churn: bool) {
+        #[cfg(feature = "metrics")]
+        super::TimerStats::global().record_deadline(at.saturating_duration_since(Instant::now()));
+        self.terminated = false;
+        self.deadline = at;
+        if self._reset(at, churn).is_err() {
             self.state = None
         }
     }
 
-    fn _reset(&mut self, dur: Duration) -> Result<(), ()> {
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn _reset(&mut self, at: Instant, churn: bool) -> Result<(), ()> {
         let state = match self.state {
             Some(ref state) => state,
             None => return Err(()),
@@ -92,43 +280,140 @@ impl Delay {
                     Err(s) => bits = s,
                 }
             }
-            *state.at.lock().unwrap() = Some(Instant::now() + dur);
+            *state.at.lock().unwrap() = Some(at);
+            #[cfg(feature = "metrics")]
+            {
+                super::TimerStats::global().record_reset();
+                let resets = state.resets.fetch_add(churn as usize, SeqCst) + 1;
+                if churn && cfg!(debug_assertions) && resets == super::stats::RESET_CHURN_WARNING {
+                    eprintln!(
+                        "futures-timer: a `Delay` has been reset {} times without \
+                         firing, it may be getting reset on every poll",
+                        resets
+                    );
+                }
+            }
             // If we fail to push our node then we've become an inert timer, so
             // we'll want to clear our `state` field accordingly
             timeouts.list.push(state)?;
-            timeouts.waker.wake();
+            if timeouts.needs_wake(at) {
+                timeouts.waker.wake();
+            }
         }
 
         Ok(())
     }
+
+    /// Splits this delay into a trigger which can fire it early and a waiter
+    /// which resolves once it has fired.
+    ///
+    /// The waiter resolves either when the original deadline passes or when
+    /// `DelayTrigger::fire` is called, whichever comes first. The trigger may
+    /// be sent to and fired from any thread.
+    pub fn split(self) -> (DelayTrigger, DelayWaiter) {
+        let trigger = DelayTrigger {
+            state: self.state.clone(),
+        };
+        (trigger, DelayWaiter { delay: self })
+    }
+}
+
+/// Returns how long from `now` (since the Unix epoch) until the next time the
+/// local clock in the timezone `tz_offset` seconds east of UTC reads `target`
+/// seconds past midnight.
+fn until_time_of_day(now: Duration, tz_offset: i32, target: u32) -> Duration {
+    const DAY: i128 = 86_400 * 1_000_000_000;
+    let local = now.as_nanos() as i128 + i128::from(tz_offset) * 1_000_000_000;
+    let into_day = local.rem_euclid(DAY);
+    let mut wait = i128::from(target) * 1_000_000_000 - into_day;
+    if wait < 0 {
+        wait += DAY;
+    }
+    Duration::from_nanos(wait as u64)
+}
+
+/// The half of a split `Delay` which can fire it early.
+///
+/// Created by `Delay::split`.
+#[derive(Clone)]
+pub struct DelayTrigger {
+    state: Option<Arc<Node<ScheduledTimer>>>,
+}
+
+impl DelayTrigger {
+    /// Fires the associated `DelayWaiter` immediately.
+    ///
+    /// This has no effect if the waiter has already fired, either through a
+    /// previous call to this method or because its deadline passed.
+    pub fn fire(&self) {
+        if let Some(ref state) = self.state {
+            if state.state.fetch_or(0b01, SeqCst) & 0b01 == 0 {
+                state.waker.wake();
+            }
+        }
+    }
+}
+
+impl fmt::Debug for DelayTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayTrigger").finish()
+    }
+}
+
+/// The half of a split `Delay` which waits for it to fire.
+///
+/// Created by `Delay::split`.
+#[derive(Debug)]
+pub struct DelayWaiter {
+    delay: Delay,
+}
+
+impl Future for DelayWaiter {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.delay).poll(cx)
+    }
 }
 
 impl Future for Delay {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let state = match self.state {
+        let this = self.get_mut();
+        let state = match this.state {
             Some(ref state) => state,
             None => panic!("timer has gone away"),
         };
 
         if state.state.load(SeqCst) & 1 != 0 {
+            this.terminated = true;
             return Poll::Ready(());
         }
 
-        state.waker.register(&cx.waker());
+        state.waker.register(cx.waker());
 
         // Now that we've registered, do the full check of our own internal
         // state. If we've fired the first bit is set, and if we've been
         // invalidated the second bit is set.
         match state.state.load(SeqCst) {
-            n if n & 0b01 != 0 => Poll::Ready(()),
+            n if n & 0b01 != 0 => {
+                this.terminated = true;
+                Poll::Ready(())
+            }
             n if n & 0b10 != 0 => panic!("timer has gone away"),
             _ => Poll::Pending,
         }
     }
 }
 
+#[cfg(feature = "futures-core")]
+impl FusedFuture for Delay {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
 impl Drop for Delay {
     fn drop(&mut self) {
         let state = match self.state {
@@ -149,3 +434,75 @@ impl fmt::Debug for Delay {
         f.debug_struct("Delay").finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::until_time_of_day;
+    use std::time::Duration;
+
+    const HOUR: u64 = 3600;
+
+    #[test]
+    fn later_today() {
+        // 01:00 UTC, waiting for 03:00 UTC
+        let now = Duration::from_secs(10 * 86_400 + HOUR);
+        let wait = until_time_of_day(now, 0, 3 * 3600);
+        assert_eq!(wait, Duration::from_secs(2 * HOUR));
+    }
+
+    #[test]
+    fn already_passed_rolls_over() {
+        // 05:00 UTC, waiting for 03:00 UTC tomorrow
+        let now = Duration::from_secs(10 * 86_400 + 5 * HOUR);
+        let wait = until_time_of_day(now, 0, 3 * 3600);
+        assert_eq!(wait, Duration::from_secs(22 * HOUR));
+    }
+
+    #[test]
+    fn sub_second_rollover() {
+        // Just past 03:00:00, so the next occurrence is nearly a day away
+        let now = Duration::from_secs(10 * 86_400 + 3 * HOUR) + Duration::from_millis(1);
+        let wait = until_time_of_day(now, 0, 3 * 3600);
+        assert_eq!(
+            wait,
+            Duration::from_secs(24 * HOUR) - Duration::from_millis(1)
+        );
+    }
+
+    #[test]
+    fn exactly_now() {
+        let now = Duration::from_secs(10 * 86_400 + 3 * HOUR);
+        assert_eq!(until_time_of_day(now, 0, 3 * 3600), Duration::from_secs(0));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn rearm_is_not_churn() {
+        use super::super::Timer;
+        use super::{Delay, SeqCst};
+
+        let timer = Timer::new();
+        let mut delay = Delay::new_handle(Duration::from_secs(60), timer.handle());
+        for _ in 0..10 {
+            delay.rearm(Duration::from_secs(60));
+        }
+        let resets = |delay: &Delay| delay.state.as_ref().unwrap().resets.load(SeqCst);
+        assert_eq!(resets(&delay), 0);
+
+        delay.reset(Duration::from_secs(60));
+        assert_eq!(resets(&delay), 1);
+    }
+
+    #[test]
+    fn offsets() {
+        // 23:00 UTC is 01:00 at UTC+2, so 03:00 local is two hours away
+        let now = Duration::from_secs(10 * 86_400 + 23 * HOUR);
+        let wait = until_time_of_day(now, 2 * 3600, 3 * 3600);
+        assert_eq!(wait, Duration::from_secs(2 * HOUR));
+
+        // 01:00 UTC is 20:00 the previous day at UTC-5
+        let now = Duration::from_secs(10 * 86_400 + HOUR);
+        let wait = until_time_of_day(now, -5 * 3600, 3 * 3600);
+        assert_eq!(wait, Duration::from_secs(7 * HOUR));
+    }
+}
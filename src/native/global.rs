@@ -2,12 +2,12 @@ use std::future::Future;
 use std::io;
 use std::mem::{self, ManuallyDrop};
 use std::pin::Pin;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{self, AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
 use std::task::{Context, RawWaker, RawWakerVTable, Waker};
 use std::thread;
 use std::thread::Thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use super::{Timer, TimerHandle};
 
@@ -38,37 +38,201 @@ impl HelperThread {
         self.timer.clone()
     }
 
-    pub fn forget(mut self) {
-        self.thread.take();
+    /// Installs this helper thread as the one powering the global timer so it
+    /// can later be torn down by `shutdown_global`.
+    ///
+    /// If `shutdown_global` has already been called then this helper thread
+    /// is immediately shut down instead.
+    pub fn install_global(self) {
+        let ptr = Box::into_raw(Box::new(self)) as usize;
+        if GLOBAL_HELPER
+            .compare_exchange(0, ptr, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            drop(unsafe { Box::from_raw(ptr as *mut HelperThread) });
+        }
     }
-}
 
-impl Drop for HelperThread {
-    fn drop(&mut self) {
+    fn shutdown(&mut self) -> io::Result<()> {
         let thread = match self.thread.take() {
             Some(thread) => thread,
-            None => return,
+            None => return Ok(()),
         };
         self.done.store(true, Ordering::SeqCst);
         thread.thread().unpark();
-        drop(thread.join());
+        thread
+            .join()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "timer thread panicked"))
+    }
+}
+
+impl Drop for HelperThread {
+    fn drop(&mut self) {
+        drop(self.shutdown());
+    }
+}
+
+// The global helper thread, if one has been spawned. This is 0 before the
+// global timer is initialized, `SHUT_DOWN` after `shutdown_global` is called,
+// and otherwise a `Box<HelperThread>` converted to a `usize`.
+static GLOBAL_HELPER: AtomicUsize = AtomicUsize::new(0);
+const SHUT_DOWN: usize = 1;
+
+/// Shuts down the global timer's helper thread, waiting for it to exit.
+///
+/// The global timer is lazily spun up in a helper thread the first time a
+/// `Delay` is created and normally lives for the rest of the process. This
+/// function is primarily intended for tests and controlled process shutdown
+/// where that lingering thread is undesirable.
+///
+/// Once this is called the global timer is torn down and will not be spawned
+/// again, unless `reset_global` is called later. Any `Delay` still pending on
+/// it, as well as any `Delay` created afterwards with `Delay::new`, is inert
+/// and will panic when polled. This includes the delays inside combinators
+/// such as `FutureExt::timeout`. Calling this more than once, or before the
+/// global timer was ever used, is allowed.
+///
+/// Code which may run after shutdown can create its delays with
+/// `Delay::try_new` instead, which returns an error rather than an inert
+/// delay. Alternatively it can bind its delays to a separately owned `Timer`
+/// with `Delay::new_handle`, which is unaffected by this function. To have
+/// the global timer spawned again on next use instead, use `reset_global`.
+///
+/// # Errors
+///
+/// Returns an error if the helper thread panicked.
+pub fn shutdown_global() -> io::Result<()> {
+    RESPAWN.store(false, Ordering::SeqCst);
+    match GLOBAL_HELPER.swap(SHUT_DOWN, Ordering::SeqCst) {
+        0 => {
+            // Nothing has been spawned yet, so install a fallback which can
+            // never be upgraded to prevent one from being spawned later. If
+            // this fails then a helper is concurrently being spawned, and it
+            // will see `SHUT_DOWN` and tear itself down.
+            let dead = TimerHandle { inner: Weak::new() };
+            let _ = dead.set_as_global_fallback();
+            Ok(())
+        }
+        SHUT_DOWN => Ok(()),
+        ptr => unsafe { Box::from_raw(ptr as *mut HelperThread) }.shutdown(),
+    }
+}
+
+// Whether the global timer is spawned again when it's used after its helper
+// thread has been stopped by `reset_global`.
+static RESPAWN: AtomicBool = AtomicBool::new(false);
+
+/// Shuts down the global timer's helper thread like `shutdown_global`, but
+/// lets a new one be spawned the next time the global timer is used.
+///
+/// Any `Delay` still pending on the old helper thread is inert and will panic
+/// when polled, but delays created afterwards with `Delay::new` work as
+/// usual. This is useful between tests which each want to start from a clean
+/// global timer. It may also be called after `shutdown_global` to allow the
+/// global timer to be used again.
+///
+/// # Errors
+///
+/// Returns an error if the helper thread panicked.
+pub fn reset_global() -> io::Result<()> {
+    RESPAWN.store(true, Ordering::SeqCst);
+    match GLOBAL_HELPER.swap(0, Ordering::SeqCst) {
+        0 | SHUT_DOWN => Ok(()),
+        ptr => unsafe { Box::from_raw(ptr as *mut HelperThread) }.shutdown(),
     }
 }
 
+pub(crate) fn respawn_enabled() -> bool {
+    RESPAWN.load(Ordering::SeqCst)
+}
+
+static MIN_WAKE_INTERVAL: AtomicU64 = AtomicU64::new(0);
+
+/// Sets the minimum amount of time the global helper thread waits between
+/// waking up to process timers.
+///
+/// By default the helper thread wakes up as soon as the next timer is due,
+/// which with many short delays can mean waking up very frequently. Setting
+/// this trades timer precision for fewer wakeups, which can matter for power
+/// consumption: a `Delay` may then fire up to `interval` late, and all timers
+/// due within that window fire together. Setting this back to zero restores
+/// the default behavior.
+pub fn set_min_wake_interval(interval: Duration) {
+    let nanos = interval.as_nanos().min(u128::from(std::u64::MAX)) as u64;
+    MIN_WAKE_INTERVAL.store(nanos, Ordering::SeqCst);
+}
+
+fn min_wake_interval() -> Duration {
+    Duration::from_nanos(MIN_WAKE_INTERVAL.load(Ordering::SeqCst))
+}
+
+static SPIN_THRESHOLD: AtomicU64 = AtomicU64::new(0);
+
+/// Sets how close to its next deadline the global helper thread stops
+/// sleeping and busy-waits instead.
+///
+/// By default the helper thread parks until the next timer is due, and the
+/// latency of being unparked by the OS can exceed the length of very short
+/// delays. With a nonzero threshold the helper thread parks only until
+/// `threshold` before the next deadline and spins for the remainder, firing
+/// timers more precisely at the cost of keeping a CPU busy while it spins.
+/// Setting this back to zero restores the default behavior.
+pub fn set_spin_threshold(threshold: Duration) {
+    let nanos = threshold.as_nanos().min(u128::from(std::u64::MAX)) as u64;
+    SPIN_THRESHOLD.store(nanos, Ordering::SeqCst);
+}
+
+fn spin_threshold() -> Duration {
+    Duration::from_nanos(SPIN_THRESHOLD.load(Ordering::SeqCst))
+}
+
 fn run(mut timer: Timer, done: Arc<AtomicBool>) {
     let waker = current_thread_waker();
     let mut cx = Context::from_waker(&waker);
 
+    let mut last_wake: Option<Instant> = None;
     while !done.load(Ordering::SeqCst) {
+        // If we woke up too soon after the last time, go back to sleep until
+        // the configured interval has passed. Any timers which became due in
+        // the meantime are then all fired together.
+        if let Some(last) = last_wake {
+            let earliest = last + min_wake_interval();
+            loop {
+                let now = Instant::now();
+                if now >= earliest || done.load(Ordering::SeqCst) {
+                    break;
+                }
+                thread::park_timeout(earliest - now);
+            }
+        }
+        last_wake = Some(Instant::now());
+        #[cfg(feature = "metrics")]
+        super::TimerStats::global().record_wakeup();
+
         let _ = Pin::new(&mut timer).poll(&mut cx);
 
         timer.advance();
-        match timer.next_event() {
+        let next = timer.next_event();
+        if !timer.prepare_park(next) {
+            continue;
+        }
+        match next {
             // Ok, block for the specified time
             Some(when) => {
                 let now = Instant::now();
                 if now < when {
-                    thread::park_timeout(when - now)
+                    let spin = spin_threshold();
+                    if when - now > spin {
+                        // Wake up early enough to spin for the last stretch.
+                        thread::park_timeout(when - now - spin)
+                    } else {
+                        while Instant::now() < when {
+                            // `std::hint::spin_loop` replaces this from Rust
+                            // 1.49 on, which is newer than we support.
+                            #[allow(deprecated)]
+                            atomic::spin_loop_hint();
+                        }
+                    }
                 } else {
                     // .. continue...
                 }
@@ -84,7 +248,7 @@ static VTABLE: RawWakerVTable = RawWakerVTable::new(raw_clone, raw_wake, raw_wak
 
 fn raw_clone(ptr: *const ()) -> RawWaker {
     let me = ManuallyDrop::new(unsafe { Arc::from_raw(ptr as *const Thread) });
-    mem::forget(me.clone());
+    mem::forget(Arc::clone(&me));
     RawWaker::new(ptr, &VTABLE)
 }
 
@@ -0,0 +1,309 @@
+//! A stream which fires repeatedly at a fixed period.
+
+use std::fmt;
+use std::future::Future;
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "futures-core")]
+use futures_core::stream::Stream;
+
+use super::{rng, Delay};
+
+/// A stream representing notifications at a fixed interval.
+///
+/// Each item is the instant at which that tick was scheduled to fire, rather
+/// than the instant it was actually observed at, so consumers can measure how
+/// far behind the schedule they are.
+///
+/// If the stream isn't polled for longer than a period some ticks are missed.
+/// An interval created with `Interval::new` or `Interval::new_at` then bursts
+/// to catch up: every missed tick is yielded immediately, one after another,
+/// until the stream is back on schedule. An interval created with
+/// `Interval::new_skipping` instead drops the missed ticks and resumes with
+/// the next tick on the original schedule which is still in the future, so it
+/// never accumulates a backlog.
+#[derive(Debug)]
+pub struct Interval {
+    delay: Delay,
+    next: Instant,
+    period: Duration,
+    skip_missed: bool,
+    drift: Duration,
+}
+
+impl Interval {
+    /// Creates a new interval which will fire every `period`, starting
+    /// `period` from now.
+    ///
+    /// Missed ticks are yielded in a burst to catch up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(period: Duration) -> Interval {
+        Interval::new_at(Instant::now() + period, period)
+    }
+
+    /// Creates a new interval which will first fire at `at` and then every
+    /// `period` after that.
+    ///
+    /// Missed ticks are yielded in a burst to catch up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new_at(at: Instant, period: Duration) -> Interval {
+        assert!(period > Duration::from_secs(0), "`period` must be non-zero");
+        Interval {
+            delay: Delay::new_at(at),
+            next: at,
+            period,
+            skip_missed: false,
+            drift: Duration::from_secs(0),
+        }
+    }
+
+    /// Creates a new interval which will fire every `period`, starting
+    /// `period` from now, and which skips missed ticks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new_skipping(period: Duration) -> Interval {
+        let mut interval = Interval::new(period);
+        interval.skip_missed = true;
+        interval
+    }
+
+    /// Creates a stream which ticks at random intervals within `range`.
+    ///
+    /// The spacing before each tick is picked with the same generator as
+    /// `Delay::new_in_range`, so `Delay::seed_in_range` makes the sequence of
+    /// spacings reproducible. If `range` is empty every tick is `range.start`
+    /// apart. See `Interval::with_spacing` for how ticks are scheduled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range.start` is zero.
+    pub fn random(range: Range<Duration>) -> Jittered {
+        assert!(
+            range.start > Duration::from_secs(0),
+            "`range` must not include zero"
+        );
+        Jittered {
+            ticks: Ticks::new(rng::in_range(range.clone())),
+            range,
+        }
+    }
+
+    /// Creates a stream which ticks with the spacing before each tick
+    /// returned by `spacing`.
+    ///
+    /// The first tick fires the first spacing from now, and each following
+    /// tick fires the next spacing after the previous tick was scheduled.
+    /// Like an interval created with `Interval::new`, missed ticks are yielded
+    /// in a burst to catch up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `spacing` returns zero, either here for the first tick or
+    /// when polled for a later one.
+    pub fn with_spacing<F: FnMut() -> Duration>(mut spacing: F) -> Spaced<F> {
+        Spaced {
+            ticks: Ticks::new(checked(spacing())),
+            spacing,
+        }
+    }
+
+    /// Converts this interval into one which yields each tick along with the
+    /// number of ticks missed just before it.
+    ///
+    /// Missed ticks are never yielded individually by the returned stream,
+    /// even if this interval was created to burst, but are instead counted.
+    /// Under normal operation the count is zero; after the consumer or the
+    /// executor stalls it is how many ticks fell behind, which makes it a
+    /// useful signal for alerting on a starved executor.
+    pub fn with_miss_count(self) -> WithMissCount {
+        WithMissCount { interval: self }
+    }
+
+    /// Returns the total time by which ticks were observed late.
+    ///
+    /// For every tick yielded so far this adds up how long after its scheduled
+    /// instant it was yielded. Ticks skipped by `Interval::new_skipping`
+    /// aren't counted. A total which keeps growing means the consumer isn't
+    /// keeping up with the period, even if it never falls a whole tick
+    /// behind.
+    pub fn cumulative_drift(&self) -> Duration {
+        self.drift
+    }
+
+    /// Polls for the next tick, returning the instant it was scheduled at.
+    ///
+    /// This is what the `Stream` implementation uses, and is available for
+    /// driving an interval without the `futures-core` feature.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        let skip = self.skip_missed;
+        self.poll_tick_counted(cx, skip).map(|(tick, _)| tick)
+    }
+
+    // Polls for the next tick, skipping missed ticks if `skip` is set, and
+    // returns the tick along with how many ticks were skipped.
+    fn poll_tick_counted(&mut self, cx: &mut Context<'_>, skip: bool) -> Poll<(Instant, u32)> {
+        let now = Instant::now();
+        if now < self.next && Pin::new(&mut self.delay).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        let tick = self.next;
+        self.drift += now.saturating_duration_since(tick);
+        let mut next = tick + self.period;
+        let mut missed = 0;
+        if skip && next <= now {
+            let period = self.period.as_nanos();
+            let behind = (now - tick).as_nanos() / period + 1;
+            next = tick + Duration::from_nanos((behind * period) as u64);
+            missed = (behind - 1).min(u128::from(std::u32::MAX)) as u32;
+        }
+        self.next = next;
+        self.delay.rearm_at(next);
+        Poll::Ready((tick, missed))
+    }
+}
+
+#[cfg(feature = "futures-core")]
+impl Stream for Interval {
+    type Item = Instant;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_tick(cx).map(Some)
+    }
+}
+
+/// Stream returned by `Interval::with_miss_count`.
+///
+/// Each item is the instant a tick was scheduled at along with the number of
+/// ticks missed since the previous item.
+#[derive(Debug)]
+pub struct WithMissCount {
+    interval: Interval,
+}
+
+impl WithMissCount {
+    /// Polls for the next tick, returning the instant it was scheduled at and
+    /// the number of ticks missed before it.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<(Instant, u32)> {
+        self.interval.poll_tick_counted(cx, true)
+    }
+}
+
+#[cfg(feature = "futures-core")]
+impl Stream for WithMissCount {
+    type Item = (Instant, u32);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_tick(cx).map(Some)
+    }
+}
+
+fn checked(spacing: Duration) -> Duration {
+    assert!(spacing > Duration::from_secs(0), "spacing must be non-zero");
+    spacing
+}
+
+// The schedule shared by `Jittered` and `Spaced`, where each tick is some
+// spacing after the previous one.
+#[derive(Debug)]
+struct Ticks {
+    delay: Delay,
+    next: Instant,
+}
+
+impl Ticks {
+    fn new(first: Duration) -> Ticks {
+        let next = Instant::now() + first;
+        Ticks {
+            delay: Delay::new_at(next),
+            next,
+        }
+    }
+
+    fn poll_tick(
+        &mut self,
+        cx: &mut Context<'_>,
+        spacing: impl FnOnce() -> Duration,
+    ) -> Poll<Instant> {
+        if Instant::now() < self.next && Pin::new(&mut self.delay).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        let tick = self.next;
+        self.next = tick + spacing();
+        self.delay.rearm_at(self.next);
+        Poll::Ready(tick)
+    }
+}
+
+/// Stream returned by `Interval::random`.
+///
+/// Each item is the instant at which that tick was scheduled to fire.
+#[derive(Debug)]
+pub struct Jittered {
+    ticks: Ticks,
+    range: Range<Duration>,
+}
+
+impl Jittered {
+    /// Polls for the next tick, returning the instant it was scheduled at.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        let range = &self.range;
+        self.ticks.poll_tick(cx, || rng::in_range(range.clone()))
+    }
+}
+
+#[cfg(feature = "futures-core")]
+impl Stream for Jittered {
+    type Item = Instant;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_tick(cx).map(Some)
+    }
+}
+
+/// Stream returned by `Interval::with_spacing`.
+///
+/// Each item is the instant at which that tick was scheduled to fire.
+pub struct Spaced<F> {
+    ticks: Ticks,
+    spacing: F,
+}
+
+impl<F: FnMut() -> Duration> Spaced<F> {
+    /// Polls for the next tick, returning the instant it was scheduled at.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        let spacing = &mut self.spacing;
+        self.ticks.poll_tick(cx, || checked(spacing()))
+    }
+}
+
+impl<F> Unpin for Spaced<F> {}
+
+#[cfg(feature = "futures-core")]
+impl<F: FnMut() -> Duration> Stream for Spaced<F> {
+    type Item = Instant;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_tick(cx).map(Some)
+    }
+}
+
+impl<F> fmt::Debug for Spaced<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Spaced")
+            .field("ticks", &self.ticks)
+            .finish()
+    }
+}
@@ -0,0 +1,93 @@
+//! A tiny, non-cryptographic PRNG used to pick randomized durations.
+
+use std::cell::Cell;
+use std::ops::Range;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+thread_local!(static STATE: Cell<u64> = Cell::new(initial_seed()));
+
+/// Reseeds the current thread's generator.
+pub fn seed(seed: u64) {
+    STATE.with(|state| state.set(mix(seed)));
+}
+
+/// Picks a duration within `range` using the current thread's generator.
+///
+/// If `range` is empty its start is returned.
+pub fn in_range(range: Range<Duration>) -> Duration {
+    let span = match range.end.checked_sub(range.start) {
+        Some(span) if span > Duration::from_secs(0) => span,
+        _ => return range.start,
+    };
+    let span = span.as_nanos().min(u128::from(std::u64::MAX));
+    let offset = (u128::from(next()) * span) >> 64;
+    range.start + Duration::from_nanos(offset as u64)
+}
+
+fn next() -> u64 {
+    // xorshift64*
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    })
+}
+
+fn initial_seed() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let local = 0u8;
+    mix(now ^ (&local as *const u8 as u64))
+}
+
+/// splitmix64, used to turn arbitrary seeds into a nonzero xorshift state.
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    if z == 0 {
+        1
+    } else {
+        z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_in_range() {
+        let range = Duration::from_millis(10)..Duration::from_millis(20);
+        for _ in 0..1000 {
+            let dur = in_range(range.clone());
+            assert!(range.contains(&dur), "{:?} out of range", dur);
+        }
+    }
+
+    #[test]
+    fn reproducible() {
+        let range = Duration::from_secs(0)..Duration::from_secs(60);
+        seed(42);
+        let a = (0..10).map(|_| in_range(range.clone())).collect::<Vec<_>>();
+        seed(42);
+        let b = (0..10).map(|_| in_range(range.clone())).collect::<Vec<_>>();
+        assert_eq!(a, b);
+        seed(43);
+        let c = (0..10).map(|_| in_range(range.clone())).collect::<Vec<_>>();
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn empty_range() {
+        let d = Duration::from_secs(1);
+        assert_eq!(in_range(d..d), d);
+        assert_eq!(in_range(d..Duration::from_secs(0)), d);
+    }
+}
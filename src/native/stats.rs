@@ -0,0 +1,150 @@
+//! Process-wide counters describing how `Delay` is being used.
+//!
+//! This module is only compiled in with the `metrics` feature so that the
+//! bookkeeping here costs nothing when unused.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+/// Number of times a single `Delay` may be reset without firing before a
+/// warning is printed in debug builds. Resets made by this crate's own
+/// adapters, which are expected to reset their delays often, aren't counted.
+pub(crate) const RESET_CHURN_WARNING: usize = 10_000;
+
+/// Statistics about all delays created in this process.
+///
+/// A reference to the global instance is acquired through
+/// `TimerStats::global`. All counters are cumulative since `TimerStats::start`.
+/// Reading them never modifies any state, so independent consumers don't
+/// interfere with each other; computing a rate over a custom window is a
+/// matter of sampling a counter twice and dividing by the time in between.
+#[derive(Debug)]
+pub struct TimerStats {
+    deadlines: [AtomicUsize; 5],
+    resets: AtomicUsize,
+    wakeups: AtomicUsize,
+}
+
+/// A snapshot of how far into the future delays were scheduled, returned by
+/// `TimerStats::deadline_distribution`.
+///
+/// Each field counts the number of calls to `Delay::new` or `Delay::reset`
+/// whose duration fell into the corresponding bucket.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeadlineDistribution {
+    /// Delays scheduled less than 10ms into the future.
+    pub under_10ms: usize,
+    /// Delays scheduled at least 10ms but less than 100ms into the future.
+    pub under_100ms: usize,
+    /// Delays scheduled at least 100ms but less than 1s into the future.
+    pub under_1s: usize,
+    /// Delays scheduled at least 1s but less than 10s into the future.
+    pub under_10s: usize,
+    /// Delays scheduled 10s or more into the future.
+    pub longer: usize,
+}
+
+static STATS: TimerStats = TimerStats {
+    deadlines: [
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+    ],
+    resets: AtomicUsize::new(0),
+    wakeups: AtomicUsize::new(0),
+};
+
+// When the counters started collecting. `START_AT` is only written inside
+// `START.call_once`, and only read once that has returned.
+static START: Once = Once::new();
+static mut START_AT: Option<Instant> = None;
+
+impl TimerStats {
+    /// Returns the statistics shared by every timer in this process.
+    pub fn global() -> &'static TimerStats {
+        &STATS
+    }
+
+    /// Returns the instant from which the counters have been collecting.
+    ///
+    /// This is when the first `Delay` was created, or now if none has been
+    /// created yet.
+    pub fn start(&self) -> Instant {
+        START.call_once(|| unsafe { START_AT = Some(Instant::now()) });
+        unsafe { START_AT }.expect("set by `call_once`")
+    }
+
+    /// Returns how many delays have been scheduled into each distance bucket.
+    ///
+    /// The distance is measured at registration time, that is when
+    /// `Delay::new` or `Delay::reset` is called.
+    pub fn deadline_distribution(&self) -> DeadlineDistribution {
+        DeadlineDistribution {
+            under_10ms: self.deadlines[0].load(Relaxed),
+            under_100ms: self.deadlines[1].load(Relaxed),
+            under_1s: self.deadlines[2].load(Relaxed),
+            under_10s: self.deadlines[3].load(Relaxed),
+            longer: self.deadlines[4].load(Relaxed),
+        }
+    }
+
+    /// Returns the total number of calls to `Delay::reset`.
+    pub fn resets(&self) -> usize {
+        self.resets.load(Relaxed)
+    }
+
+    /// Returns the average number of calls to `Delay::reset` per second since
+    /// `TimerStats::start`.
+    ///
+    /// A persistently high rate often means a delay is being reset on every
+    /// poll, so it never fires and instead burns CPU.
+    pub fn reset_rate(&self) -> f64 {
+        self.rate(self.resets())
+    }
+
+    /// Returns the total number of times the global helper thread has woken
+    /// up to process timers.
+    pub fn wakeups(&self) -> usize {
+        self.wakeups.load(Relaxed)
+    }
+
+    /// Returns the average number of times per second the global helper thread
+    /// has woken up to process timers since `TimerStats::start`.
+    ///
+    /// See `set_min_wake_interval` for capping this rate.
+    pub fn wakeups_per_sec(&self) -> f64 {
+        self.rate(self.wakeups())
+    }
+
+    fn rate(&self, count: usize) -> f64 {
+        let secs = self.start().elapsed().as_secs_f64();
+        if secs == 0.0 {
+            return 0.0;
+        }
+        count as f64 / secs
+    }
+
+    pub(crate) fn record_reset(&self) {
+        self.resets.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn record_wakeup(&self) {
+        self.wakeups.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn record_deadline(&self, dur: Duration) {
+        self.start();
+        let bucket = match dur.as_millis() {
+            0..=9 => 0,
+            10..=99 => 1,
+            100..=999 => 2,
+            1000..=9999 => 3,
+            _ => 4,
+        };
+        self.deadlines[bucket].fetch_add(1, Relaxed);
+    }
+}
@@ -1,11 +1,12 @@
 use std::fmt;
+use std::io;
 use std::mem;
 use std::pin::Pin;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
+use std::sync::atomic::{AtomicU64, AtomicUsize};
 use std::sync::{Arc, Mutex, Weak};
 use std::task::{Context, Poll};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use std::future::Future;
 
@@ -15,13 +16,14 @@ use super::{global, ArcList, Heap, HeapTimer, Node, Slot};
 /// A "timer heap" used to power separately owned instances of `Delay`.
 ///
 /// This timer is implemented as a priority queued-based heap. Each `Timer`
-/// contains a few primary methods which which to drive it:
+/// contains a few primary methods with which to drive it:
 ///
-/// * `next_wake` indicates how long the ambient system needs to sleep until it
-///   invokes further processing on a `Timer`
+/// * `next_event` returns the instant until which the ambient system can sleep
+///   before it needs to invoke further processing on a `Timer`
 /// * `advance_to` is what actually fires timers on the `Timer`, and should be
-///   called essentially every iteration of the event loop, or when the time
-///   specified by `next_wake` has elapsed.
+///   called essentially every iteration of the event loop, or when the instant
+///   returned by `next_event` has been reached. `advance` is the same as
+///   `advance_to(Instant::now())`.
 /// * The `Future` implementation for `Timer` is used to process incoming timer
 ///   updates and requests. This is used to schedule new timeouts, update
 ///   existing ones, or delete existing timeouts. The `Future` implementation
@@ -30,8 +32,9 @@ use super::{global, ArcList, Heap, HeapTimer, Node, Slot};
 ///
 /// Note that if you're using this crate you probably don't need to use a
 /// `Timer` as there is a global one already available for you run on a helper
-/// thread. If this isn't desirable, though, then the
-/// `TimerHandle::set_fallback` method can be used instead!
+/// thread. A `Timer` driven manually is mostly useful in tests, where delays
+/// created with `Delay::new_handle` fire exactly when the test calls
+/// `advance_to`, regardless of how much real time has passed.
 pub struct Timer {
     inner: Arc<Inner>,
     timer_heap: Heap<HeapTimer>,
@@ -49,8 +52,19 @@ pub(crate) struct Inner {
 
     /// The blocked `Timer` task to receive notifications to the `list` above.
     pub(crate) waker: AtomicWaker,
+
+    /// The instant the `Timer` task is sleeping until, in nanoseconds since
+    /// `created`, or `NO_DEADLINE` if it isn't known to wake up on its own.
+    parked_until: AtomicU64,
+    created: Instant,
+
+    /// The timeout used by `FutureExt::timeout_default`, in nanoseconds, or
+    /// `NO_DEADLINE` if none has been set.
+    default_timeout: AtomicU64,
 }
 
+const NO_DEADLINE: u64 = std::u64::MAX;
+
 /// Shared state between the `Timer` and a `Delay`.
 pub(crate) struct ScheduledTimer {
     pub(crate) waker: AtomicWaker,
@@ -67,6 +81,10 @@ pub(crate) struct ScheduledTimer {
     // TODO: this is only accessed by the timer thread, should have a more
     // lightweight protection than a `Mutex`
     pub(crate) slot: Mutex<Option<Slot>>,
+
+    // Number of times this timer has been reset since it last fired.
+    #[cfg(feature = "metrics")]
+    pub(crate) resets: AtomicUsize,
 }
 
 impl Timer {
@@ -76,6 +94,9 @@ impl Timer {
             inner: Arc::new(Inner {
                 list: ArcList::new(),
                 waker: AtomicWaker::new(),
+                parked_until: AtomicU64::new(NO_DEADLINE),
+                created: Instant::now(),
+                default_timeout: AtomicU64::new(NO_DEADLINE),
             }),
             timer_heap: Heap::new(),
         }
@@ -97,15 +118,31 @@ impl Timer {
         self.timer_heap.peek().map(|t| t.at)
     }
 
-    /// Proces any timers which are supposed to fire at or before the current
-    /// instant.
+    /// Records that the task driving this timer is about to sleep until
+    /// `when`, or indefinitely if `None`.
+    ///
+    /// While sleeping until `when` the task won't be woken up for new timers
+    /// which fire at or after `when`, as it'll process them once it wakes up
+    /// anyway. Returns `false` if updates arrived in the meantime, in which
+    /// case the timer must be polled again instead of going to sleep.
+    pub(crate) fn prepare_park(&self, when: Option<Instant>) -> bool {
+        let until = match when {
+            Some(when) => self.inner.nanos(when),
+            None => NO_DEADLINE,
+        };
+        self.inner.parked_until.store(until, SeqCst);
+        self.inner.list.is_empty()
+    }
+
+    /// Processes any timers which are supposed to fire at or before the
+    /// current instant.
     ///
     /// This method is equivalent to `self.advance_to(Instant::now())`.
     pub fn advance(&mut self) {
         self.advance_to(Instant::now())
     }
 
-    /// Proces any timers which are supposed to fire before `now` specified.
+    /// Processes any timers which are supposed to fire at or before `now`.
     ///
     /// This method should be called on `Timer` periodically to advance the
     /// internal state and process any pending timers which need to fire.
@@ -127,7 +164,11 @@ impl Timer {
                 .state
                 .compare_exchange(bits, bits | 0b01, SeqCst, SeqCst)
             {
-                Ok(_) => heap_timer.node.waker.wake(),
+                Ok(_) => {
+                    #[cfg(feature = "metrics")]
+                    heap_timer.node.resets.store(0, SeqCst);
+                    heap_timer.node.waker.wake()
+                }
                 Err(_b) => {}
             }
         }
@@ -168,6 +209,23 @@ impl Timer {
     }
 }
 
+impl Inner {
+    /// Returns whether the `Timer` task needs to be woken up to process a
+    /// timer which fires at `at`, rather than waiting for its next deadline.
+    pub(crate) fn needs_wake(&self, at: Instant) -> bool {
+        self.nanos(at) < self.parked_until.load(SeqCst)
+    }
+
+    fn nanos(&self, at: Instant) -> u64 {
+        let nanos = at.saturating_duration_since(self.created).as_nanos();
+        if nanos >= u128::from(NO_DEADLINE) {
+            NO_DEADLINE - 1
+        } else {
+            nanos as u64
+        }
+    }
+}
+
 impl Future for Timer {
     type Output = ();
 
@@ -218,9 +276,9 @@ impl Default for Timer {
 
 static HANDLE_FALLBACK: AtomicUsize = AtomicUsize::new(0);
 
-/// Error returned from `TimerHandle::set_fallback`.
+/// Error returned from `TimerHandle::set_as_global_fallback`.
 #[derive(Clone, Debug)]
-struct SetDefaultError(());
+pub(crate) struct SetDefaultError(());
 
 impl TimerHandle {
     /// Configures this timer handle to be the one returned by
@@ -245,7 +303,7 @@ impl TimerHandle {
     /// thread otherwise loses a race to call this method then it will fail
     /// returning an error. Once a call to `set_as_global_fallback` is
     /// successful then no future calls may succeed.
-    fn set_as_global_fallback(self) -> Result<(), SetDefaultError> {
+    pub(crate) fn set_as_global_fallback(self) -> Result<(), SetDefaultError> {
         unsafe {
             let val = self.into_usize();
             match HANDLE_FALLBACK.compare_exchange(0, val, SeqCst, SeqCst) {
@@ -258,6 +316,30 @@ impl TimerHandle {
         }
     }
 
+    /// Sets the timeout applied by `FutureExt::timeout_default` to futures
+    /// bound to this handle's timer.
+    ///
+    /// The default is shared by every handle to the same timer, so this is a
+    /// way of configuring timeout policy once for everything driven by that
+    /// timer. Changing it affects timeouts created afterwards, not ones which
+    /// already exist. This has no effect if the timer has gone away.
+    pub fn set_default_timeout(&self, dur: Duration) {
+        if let Some(inner) = self.inner.upgrade() {
+            let nanos = dur.as_nanos().min(u128::from(NO_DEADLINE - 1)) as u64;
+            inner.default_timeout.store(nanos, SeqCst);
+        }
+    }
+
+    /// Returns the timeout set with `TimerHandle::set_default_timeout`, if
+    /// any.
+    pub fn default_timeout(&self) -> Option<Duration> {
+        let inner = self.inner.upgrade()?;
+        match inner.default_timeout.load(SeqCst) {
+            NO_DEADLINE => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+
     fn into_usize(self) -> usize {
         unsafe { mem::transmute::<Weak<Inner>, usize>(self.inner) }
     }
@@ -266,34 +348,29 @@ impl TimerHandle {
         let inner = mem::transmute::<usize, Weak<Inner>>(val);
         TimerHandle { inner }
     }
-}
 
-impl Default for TimerHandle {
-    fn default() -> TimerHandle {
+    /// Returns the handle `TimerHandle::default` would, but fails with the
+    /// underlying error if the global helper thread couldn't be spawned
+    /// rather than returning a defunkt handle.
+    pub(crate) fn try_default() -> io::Result<TimerHandle> {
         let mut fallback = HANDLE_FALLBACK.load(SeqCst);
 
         // If the fallback hasn't been previously initialized then let's spin
-        // up a helper thread and try to initialize with that. If we can't
-        // actually create a helper thread then we'll just return a "defunkt"
-        // handle which will return errors when timer objects are attempted to
-        // be associated.
+        // up a helper thread and try to initialize with that.
         if fallback == 0 {
-            let helper = match global::HelperThread::new() {
-                Ok(helper) => helper,
-                Err(_) => return TimerHandle { inner: Weak::new() },
-            };
+            let helper = global::HelperThread::new()?;
 
             // If we successfully set ourselves as the actual fallback then we
-            // want to `forget` the helper thread to ensure that it persists
-            // globally. If we fail to set ourselves as the fallback that means
-            // that someone was racing with this call to
-            // `TimerHandle::default`.  They ended up winning so we'll destroy
-            // our helper thread (which shuts down the thread) and reload the
-            // fallback.
+            // want to install the helper thread globally to ensure that it
+            // persists until `shutdown_global`. If we fail to set ourselves
+            // as the fallback that means that someone was racing with this
+            // call to `TimerHandle::default`.  They ended up winning so we'll
+            // destroy our helper thread (which shuts down the thread) and
+            // reload the fallback.
             if helper.handle().set_as_global_fallback().is_ok() {
                 let ret = helper.handle();
-                helper.forget();
-                return ret;
+                helper.install_global();
+                return Ok(ret);
             }
             fallback = HANDLE_FALLBACK.load(SeqCst);
         }
@@ -302,15 +379,46 @@ impl Default for TimerHandle {
         // its value to reify a handle, clone it, and then forget our reified
         // handle as we don't actually have an owning reference to it.
         assert!(fallback != 0);
-        unsafe {
+        let ret = unsafe {
             let handle = TimerHandle::from_usize(fallback);
             let ret = handle.clone();
             let _ = handle.into_usize();
             ret
+        };
+        if !global::respawn_enabled() || ret.inner.upgrade().is_some() {
+            return Ok(ret);
+        }
+
+        // The helper thread behind the fallback was stopped by `reset_global`,
+        // so spawn a replacement and swap it in. The old fallback is leaked
+        // rather than dropped, as other threads may be reifying it right now.
+        let helper = global::HelperThread::new()?;
+        let val = helper.handle().into_usize();
+        match HANDLE_FALLBACK.compare_exchange(fallback, val, SeqCst, SeqCst) {
+            Ok(_) => {
+                let ret = helper.handle();
+                helper.install_global();
+                Ok(ret)
+            }
+            // Someone else replaced it first, so use theirs instead.
+            Err(_) => {
+                drop(unsafe { TimerHandle::from_usize(val) });
+                drop(helper);
+                TimerHandle::try_default()
+            }
         }
     }
 }
 
+impl Default for TimerHandle {
+    fn default() -> TimerHandle {
+        // If we can't actually create a helper thread then we'll just return
+        // a "defunkt" handle which will return errors when timer objects are
+        // attempted to be associated.
+        TimerHandle::try_default().unwrap_or_else(|_| TimerHandle { inner: Weak::new() })
+    }
+}
+
 impl fmt::Debug for TimerHandle {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         f.debug_struct("TimerHandle")
@@ -318,3 +426,60 @@ impl fmt::Debug for TimerHandle {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::Delay;
+    use futures::task::{waker, ArcWake};
+    use std::time::Duration;
+
+    struct Count(AtomicUsize);
+
+    impl ArcWake for Count {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, SeqCst);
+        }
+    }
+
+    #[test]
+    fn far_future_insert_skips_wake() {
+        let mut timer = Timer::new();
+        let count = Arc::new(Count(AtomicUsize::new(0)));
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let now = Instant::now();
+        let _first = Delay::new_at_handle(now + Duration::from_secs(10), timer.handle());
+        assert!(Pin::new(&mut timer).poll(&mut cx).is_pending());
+        assert!(timer.prepare_park(timer.next_event()));
+
+        // Later than the next event, so there's no need to wake the timer
+        let mut later = Delay::new_at_handle(now + Duration::from_secs(20), timer.handle());
+        later.reset(Duration::from_secs(30));
+        assert_eq!(count.0.load(SeqCst), 0);
+
+        // Earlier than the next event, so the timer needs to wake up
+        let _sooner = Delay::new_at_handle(now + Duration::from_secs(5), timer.handle());
+        assert_eq!(count.0.load(SeqCst), 1);
+    }
+
+    #[test]
+    fn updates_prevent_park() {
+        let mut timer = Timer::new();
+        let count = Arc::new(Count(AtomicUsize::new(0)));
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let now = Instant::now();
+        let _first = Delay::new_at_handle(now + Duration::from_secs(10), timer.handle());
+        assert!(Pin::new(&mut timer).poll(&mut cx).is_pending());
+
+        // An update which arrives before the timer records where it's parking
+        // isn't processed yet, so the timer must not go to sleep.
+        let _later = Delay::new_at_handle(now + Duration::from_secs(20), timer.handle());
+        assert!(!timer.prepare_park(timer.next_event()));
+        assert!(Pin::new(&mut timer).poll(&mut cx).is_pending());
+        assert!(timer.prepare_park(timer.next_event()));
+    }
+}
@@ -0,0 +1,82 @@
+//! A future combining a `Delay` with a user-provided poll function.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::Delay;
+
+/// Future for the `poll_delay` function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PollDelay<F> {
+    delay: Delay,
+    f: F,
+}
+
+/// Creates a future which owns a `Delay` firing after `dur` and defers to `f`
+/// each time it is polled.
+///
+/// The closure receives the task context along with mutable access to the
+/// delay, so it can poll it, reset it, or ignore it entirely depending on its
+/// own logic. The returned future resolves with the value of the first
+/// `Poll::Ready` returned by `f`. Note that the delay is never polled on the
+/// closure's behalf; if `f` wants to be woken up when it fires it must poll
+/// the delay itself.
+///
+/// The closure and any state it captures are never pinned, which is why
+/// `PollDelay` is always `Unpin`. Futures which are not `Unpin` must be pinned
+/// (for example with `Box::pin`) before being captured if the closure needs to
+/// poll them.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[async_std::main]
+/// # async fn main() {
+/// use std::future::Future;
+/// use std::pin::Pin;
+/// use std::time::Duration;
+/// use futures_timer::poll_delay;
+///
+/// let mut polls = 0;
+/// let polls = poll_delay(Duration::from_secs(1), |cx, delay| {
+///     polls += 1;
+///     Pin::new(delay).poll(cx).map(|()| polls)
+/// })
+/// .await;
+/// println!("polled {} times", polls);
+/// # }
+/// ```
+pub fn poll_delay<T, F>(dur: Duration, f: F) -> PollDelay<F>
+where
+    F: FnMut(&mut Context<'_>, &mut Delay) -> Poll<T>,
+{
+    PollDelay {
+        delay: Delay::new(dur),
+        f,
+    }
+}
+
+impl<F> Unpin for PollDelay<F> {}
+
+impl<T, F> Future for PollDelay<F>
+where
+    F: FnMut(&mut Context<'_>, &mut Delay) -> Poll<T>,
+{
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let me = self.get_mut();
+        (me.f)(cx, &mut me.delay)
+    }
+}
+
+impl<F> fmt::Debug for PollDelay<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PollDelay")
+            .field("delay", &self.delay)
+            .finish()
+    }
+}
@@ -0,0 +1,90 @@
+//! Free functions for creating delays and timeouts.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+use std::time::Instant;
+
+use crate::{Delay, FutureExt, Timeout};
+
+/// Returns a future which completes once `dur` has elapsed.
+///
+/// This is an alias for `Delay::new`, so the returned delay can still be
+/// reset or otherwise used like any other `Delay`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[async_std::main]
+/// # async fn main() {
+/// use std::time::Duration;
+///
+/// futures_timer::sleep(Duration::from_secs(1)).await;
+/// # }
+/// ```
+pub fn sleep(dur: Duration) -> Delay {
+    Delay::new(dur)
+}
+
+/// Returns a future which completes at the instant `at`.
+///
+/// This is an alias for `Delay::new_at`.
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+pub fn sleep_until(at: Instant) -> Delay {
+    Delay::new_at(at)
+}
+
+/// Requires `future` to complete within `dur`.
+///
+/// This is the same as `FutureExt::timeout`, for call sites where a free
+/// function reads better than a method chain.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[async_std::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use futures_timer::Delay;
+///
+/// let slow = Delay::new(Duration::from_secs(10));
+/// let res = futures_timer::timeout(Duration::from_secs(1), slow).await;
+/// assert!(res.is_err());
+/// # }
+/// ```
+pub fn timeout<F: Future>(dur: Duration, future: F) -> Timeout<F> {
+    future.timeout(dur)
+}
+
+/// Requires the borrowed `future` to complete within `dur`.
+///
+/// This is like `timeout` except that the future is borrowed rather than
+/// moved into the returned `Timeout`, which is useful for long-lived futures
+/// stored in a state machine. If the timeout elapses the future is left
+/// untouched, so it can be given another deadline with a new call to this
+/// function, or the same `Timeout` can be extended with `Timeout::reset`.
+///
+/// The returned `Timeout` mutably borrows `future` for as long as it is
+/// alive, so it must be dropped before the future can be polled directly or
+/// wrapped again.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[async_std::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use futures_timer::{timeout_pinned, Delay};
+///
+/// let mut slow = Box::pin(Delay::new(Duration::from_secs(2)));
+/// assert!(timeout_pinned(Duration::from_secs(1), slow.as_mut()).await.is_err());
+/// assert!(timeout_pinned(Duration::from_secs(5), slow.as_mut()).await.is_ok());
+/// # }
+/// ```
+pub fn timeout_pinned<F: Future + ?Sized>(
+    dur: Duration,
+    future: Pin<&mut F>,
+) -> Timeout<Pin<&mut F>> {
+    future.timeout(dur)
+}
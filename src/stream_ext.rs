@@ -0,0 +1,447 @@
+//! Extension methods for streams.
+
+use std::future::Future;
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+use std::time::Instant;
+
+use futures_core::stream::FusedStream;
+use futures_core::Stream;
+
+use crate::Delay;
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+use crate::Interval;
+
+// How many items an adapter takes from its stream in one `poll_next` without
+// yielding any, before it yields to the executor. A stream which is always
+// ready would otherwise keep a single `poll_next` call from returning.
+const DRAIN_LIMIT: usize = 32;
+
+/// An extension trait for streams which provides rate-limiting adapters
+/// driven by `Delay`.
+pub trait StreamExt: Stream {
+    /// Yields at most one item of this stream per `dur`.
+    ///
+    /// The first item is yielded right away and opens a window of `dur`.
+    /// Items arriving while the window is open are dropped, and the next item
+    /// arriving after it has closed is yielded and opens a new window.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use futures::stream::{self, StreamExt as _};
+    /// use futures_timer::StreamExt;
+    ///
+    /// let items = stream::iter(0..10).throttle(Duration::from_secs(1));
+    /// assert_eq!(items.collect::<Vec<_>>().await, [0]);
+    /// # }
+    /// ```
+    fn throttle(self, dur: Duration) -> Throttle<Self>
+    where
+        Self: Sized,
+    {
+        Throttle {
+            stream: self,
+            dur,
+            delay: None,
+            open: false,
+        }
+    }
+
+    /// Yields an item of this stream only once `dur` has passed without a
+    /// newer item arriving.
+    ///
+    /// Each incoming item replaces the pending one and restarts the wait, so
+    /// a burst of items results in just its last item being yielded. If this
+    /// stream ends while an item is pending, that item is yielded right away
+    /// before the end of the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use futures::stream::{self, StreamExt as _};
+    /// use futures_timer::StreamExt;
+    ///
+    /// let items = stream::iter(0..10).debounce(Duration::from_millis(100));
+    /// assert_eq!(items.collect::<Vec<_>>().await, [9]);
+    /// # }
+    /// ```
+    fn debounce(self, dur: Duration) -> Debounce<Self>
+    where
+        Self: Sized,
+    {
+        Debounce {
+            stream: self,
+            dur,
+            delay: None,
+            pending: None,
+            done: false,
+        }
+    }
+
+    /// Yields the items of this stream until `dur` has passed, then ends.
+    ///
+    /// Unlike a timeout this is a clean truncation: once the deadline passes
+    /// the returned stream yields `None` rather than an error, and never polls
+    /// this stream again. If `dur` is zero it ends on the first poll.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use futures::stream::{self, StreamExt as _};
+    /// use futures_timer::StreamExt;
+    ///
+    /// let events = stream::pending::<()>().take_until_delay(Duration::from_secs(5));
+    /// assert!(events.collect::<Vec<_>>().await.is_empty());
+    /// # }
+    /// ```
+    fn take_until_delay(self, dur: Duration) -> TakeUntilDelay<Self>
+    where
+        Self: Sized,
+    {
+        TakeUntilDelay {
+            stream: self,
+            delay: Delay::new(dur),
+            done: dur == Duration::from_secs(0),
+        }
+    }
+
+    /// Yields the items of this stream until the instant `at`, then ends.
+    ///
+    /// This is the same as `take_until_delay` except that the deadline is
+    /// given as an absolute instant. If `at` has already passed the returned
+    /// stream ends on the first poll.
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    fn take_until_delay_at(self, at: Instant) -> TakeUntilDelay<Self>
+    where
+        Self: Sized,
+    {
+        TakeUntilDelay {
+            stream: self,
+            delay: Delay::new_at(at),
+            done: at <= Instant::now(),
+        }
+    }
+
+    /// Yields each item of this stream along with how long it took to arrive.
+    ///
+    /// The duration paired with each item is the time since the previous item
+    /// was yielded, or since the returned stream was first polled for the
+    /// first item. Items are passed through unchanged, so this can be used to
+    /// chart the cadence of a stream without affecting it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() {
+    /// use futures::stream::{self, StreamExt as _};
+    /// use futures_timer::StreamExt;
+    ///
+    /// let mut items = stream::iter(0..3).with_intervals();
+    /// while let Some((item, gap)) = items.next().await {
+    ///     println!("{} arrived after {:?}", item, gap);
+    /// }
+    /// # }
+    /// ```
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    fn with_intervals(self) -> WithIntervals<Self>
+    where
+        Self: Sized,
+    {
+        WithIntervals {
+            stream: self,
+            last: None,
+        }
+    }
+
+    /// Groups the items of this stream into consecutive windows of `dur`,
+    /// yielding each window's items as a `Vec`.
+    ///
+    /// Windows are closed on a fixed schedule, every `dur` from when
+    /// `windowed` is called, however many items arrive in them. Windows
+    /// without any items are skipped unless `Windowed::with_empty_windows` is
+    /// used. When this stream ends, the items of the window in progress are
+    /// yielded right away before the end of the stream, unless there are none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dur` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use futures::stream::{self, StreamExt as _};
+    /// use futures_timer::StreamExt;
+    ///
+    /// let items = stream::iter(0..3).windowed(Duration::from_secs(1));
+    /// assert_eq!(items.collect::<Vec<_>>().await, [vec![0, 1, 2]]);
+    /// # }
+    /// ```
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+    fn windowed(self, dur: Duration) -> Windowed<Self>
+    where
+        Self: Sized,
+    {
+        Windowed {
+            stream: self,
+            interval: Interval::new(dur),
+            items: Vec::new(),
+            keep_empty: false,
+            done: false,
+        }
+    }
+}
+
+impl<S: Stream + ?Sized> StreamExt for S {}
+
+/// Stream returned by `StreamExt::throttle`.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct Throttle<S> {
+    stream: S,
+    dur: Duration,
+    // Created for the first window and reset for each one after that.
+    delay: Option<Delay>,
+    open: bool,
+}
+
+impl<S: Stream> Stream for Throttle<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        // Safety: `stream` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        for _ in 0..DRAIN_LIMIT {
+            if this.open {
+                let delay = this.delay.as_mut().expect("open windows have a delay");
+                if Pin::new(delay).poll(cx).is_ready() {
+                    this.open = false;
+                }
+            }
+
+            let stream = unsafe { Pin::new_unchecked(&mut this.stream) };
+            match stream.poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if !this.open {
+                        match &mut this.delay {
+                            Some(delay) => delay.rearm(this.dur),
+                            None => this.delay = Some(Delay::new(this.dur)),
+                        }
+                        this.open = true;
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Stream returned by `StreamExt::debounce`.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct Debounce<S: Stream> {
+    stream: S,
+    dur: Duration,
+    delay: Option<Delay>,
+    pending: Option<S::Item>,
+    done: bool,
+}
+
+impl<S: Stream> Stream for Debounce<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        // Safety: `stream` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut received = 0;
+        while !this.done && received < DRAIN_LIMIT {
+            let stream = unsafe { Pin::new_unchecked(&mut this.stream) };
+            match stream.poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.pending = Some(item);
+                    received += 1;
+                }
+                Poll::Ready(None) => this.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if this.done {
+            return Poll::Ready(this.pending.take());
+        }
+        if received > 0 {
+            // Restart the wait once per batch rather than for every item, as
+            // the items in a batch arrived at practically the same time.
+            match &mut this.delay {
+                Some(delay) => delay.rearm(this.dur),
+                None => this.delay = Some(Delay::new(this.dur)),
+            }
+        }
+        if received == DRAIN_LIMIT {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        match &mut this.delay {
+            Some(delay) if this.pending.is_some() => match Pin::new(delay).poll(cx) {
+                Poll::Ready(()) => Poll::Ready(this.pending.take()),
+                Poll::Pending => Poll::Pending,
+            },
+            _ => Poll::Pending,
+        }
+    }
+}
+
+/// Stream returned by `StreamExt::take_until_delay` and
+/// `StreamExt::take_until_delay_at`.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct TakeUntilDelay<S> {
+    stream: S,
+    delay: Delay,
+    done: bool,
+}
+
+impl<S: Stream> Stream for TakeUntilDelay<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        // Safety: `stream` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.done || Pin::new(&mut this.delay).poll(cx).is_ready() {
+            this.done = true;
+            return Poll::Ready(None);
+        }
+
+        let stream = unsafe { Pin::new_unchecked(&mut this.stream) };
+        let item = stream.poll_next(cx);
+        if let Poll::Ready(None) = item {
+            this.done = true;
+        }
+        item
+    }
+}
+
+impl<S: Stream> FusedStream for TakeUntilDelay<S> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+/// Stream returned by `StreamExt::with_intervals`.
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct WithIntervals<S> {
+    stream: S,
+    // When the previous item was yielded, or the first poll happened.
+    last: Option<Instant>,
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+impl<S: Stream> Stream for WithIntervals<S> {
+    type Item = (S::Item, Duration);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Safety: `stream` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        let last = *this.last.get_or_insert_with(Instant::now);
+        let stream = unsafe { Pin::new_unchecked(&mut this.stream) };
+        match stream.poll_next(cx) {
+            Poll::Ready(Some(item)) => {
+                let now = Instant::now();
+                this.last = Some(now);
+                Poll::Ready(Some((item, now - last)))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Stream returned by `StreamExt::windowed`.
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct Windowed<S: Stream> {
+    stream: S,
+    interval: Interval,
+    items: Vec<S::Item>,
+    keep_empty: bool,
+    done: bool,
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+impl<S: Stream> Windowed<S> {
+    /// Makes this stream yield an empty `Vec` for windows in which no items
+    /// arrived, rather than skipping them.
+    ///
+    /// The final window is still skipped if this stream ends with it empty.
+    pub fn with_empty_windows(mut self) -> Windowed<S> {
+        self.keep_empty = true;
+        self
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-bindgen")))]
+impl<S: Stream> Stream for Windowed<S> {
+    type Item = Vec<S::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Safety: `stream` is structurally pinned and nothing else is.
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        let mut received = 0;
+        while received < DRAIN_LIMIT {
+            let stream = unsafe { Pin::new_unchecked(&mut this.stream) };
+            match stream.poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.items.push(item);
+                    received += 1;
+                }
+                Poll::Ready(None) => {
+                    this.done = true;
+                    if this.items.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    return Poll::Ready(Some(mem::replace(&mut this.items, Vec::new())));
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        while this.interval.poll_tick(cx).is_ready() {
+            if this.keep_empty || !this.items.is_empty() {
+                return Poll::Ready(Some(mem::replace(&mut this.items, Vec::new())));
+            }
+        }
+        if received == DRAIN_LIMIT {
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
@@ -25,6 +25,11 @@ impl Delay {
     pub fn reset(&mut self, dur: Duration) {
         *self = Delay::new(dur);
     }
+
+    /// Resets this delay like `reset`, for this crate's own adapters.
+    pub(crate) fn rearm(&mut self, dur: Duration) {
+        self.reset(dur)
+    }
 }
 
 impl Future for Delay {
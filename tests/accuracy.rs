@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+use futures_timer::measure_sleep_accuracy;
+
+#[async_std::test]
+async fn stats_are_ordered() {
+    let stats = measure_sleep_accuracy(5, Duration::from_millis(5)).await;
+    assert!(stats.min <= stats.mean);
+    assert!(stats.mean <= stats.max);
+}
+
+#[async_std::test]
+async fn no_samples() {
+    let stats = measure_sleep_accuracy(0, Duration::from_secs(10)).await;
+    assert_eq!(stats.min, Duration::from_secs(0));
+    assert_eq!(stats.mean, Duration::from_secs(0));
+    assert_eq!(stats.max, Duration::from_secs(0));
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use futures::future;
+use futures_timer::{Budget, Delay};
+
+#[async_std::test]
+async fn steps_exceed_budget() {
+    let step = Duration::from_millis(40);
+    let mut budget = Budget::new(Duration::from_millis(100));
+
+    assert!(budget.guard(Delay::new(step)).await.is_ok());
+    assert!(budget.guard(Delay::new(step)).await.is_ok());
+    assert!(budget.remaining() <= Duration::from_millis(20));
+
+    // The third step runs out the budget
+    assert!(budget.guard(Delay::new(step)).await.is_err());
+    assert_eq!(budget.remaining(), Duration::from_secs(0));
+
+    // ... after which everything fails, even futures which are ready
+    assert!(budget.guard(future::ready(())).await.is_err());
+}
+
+#[async_std::test]
+async fn ready_future_keeps_budget() {
+    let total = Duration::from_secs(10);
+    let mut budget = Budget::new(total);
+    assert_eq!(budget.guard(future::ready(1)).await, Ok(1));
+    assert!(budget.remaining() > Duration::from_secs(9));
+}
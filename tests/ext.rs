@@ -0,0 +1,146 @@
+use std::future::Future;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::future::{pending, Pending};
+
+use futures_timer::{Delay, FutureExt};
+
+#[async_std::test]
+async fn at_least_pads_fast_future() {
+    let dur = Duration::from_millis(50);
+    let i = Instant::now();
+    let value = async { 1 }.at_least(dur).await;
+    assert_eq!(value, 1);
+    assert!(i.elapsed() >= dur);
+}
+
+#[async_std::test]
+async fn at_least_does_not_pad_slow_future() {
+    let dur = Duration::from_millis(30);
+    let slow = async {
+        Delay::new(dur * 2).await;
+        Instant::now()
+    };
+    let finished = slow.at_least(dur).await;
+    assert!(finished.elapsed() < dur);
+}
+
+#[async_std::test]
+async fn timeout_completes() {
+    let res = async { 1 }.timeout(Duration::from_secs(10)).await;
+    assert_eq!(res, Ok(1));
+}
+
+#[async_std::test]
+async fn timeout_elapses() {
+    let dur = Duration::from_millis(20);
+    let i = Instant::now();
+    let res = Delay::new(Duration::from_secs(10)).timeout(dur).await;
+    assert!(res.is_err());
+    assert!(i.elapsed() >= dur);
+    assert_eq!(res.unwrap_err().to_string(), "deadline has elapsed");
+}
+
+#[async_std::test]
+async fn timeout_at() {
+    let at = Instant::now() + Duration::from_millis(20);
+    let res = Delay::new(Duration::from_secs(10)).timeout_at(at).await;
+    assert!(res.is_err());
+    assert!(Instant::now() >= at);
+}
+
+#[async_std::test]
+async fn timeout_reset_after_elapsed() {
+    let mut timeout =
+        Box::pin(Delay::new(Duration::from_millis(60)).timeout(Duration::from_millis(20)));
+    assert!(timeout.as_mut().await.is_err());
+
+    timeout.as_mut().reset(Duration::from_secs(10));
+    assert_eq!(timeout.await, Ok(()));
+}
+
+#[async_std::test]
+async fn timeout_reset_at() {
+    let mut timeout =
+        Box::pin(Delay::new(Duration::from_secs(10)).timeout(Duration::from_secs(10)));
+    let at = Instant::now() + Duration::from_millis(20);
+    timeout.as_mut().reset_at(at);
+    assert!(timeout.await.is_err());
+    assert!(Instant::now() >= at);
+}
+
+#[test]
+fn timeout_set_remaining_zero_elapses_on_next_poll() {
+    let waker = futures::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut timeout = Box::pin(pending::<()>().timeout(Duration::from_secs(10)));
+    assert!(timeout.as_mut().poll(&mut cx).is_pending());
+
+    timeout.as_mut().set_remaining(Duration::from_secs(0));
+    assert!(matches!(
+        timeout.as_mut().poll(&mut cx),
+        Poll::Ready(Err(_))
+    ));
+}
+
+#[async_std::test]
+async fn timeout_set_remaining_shares_budget() {
+    let budget = Duration::from_secs(10);
+    let mut timeout = Box::pin(pending::<()>().timeout(budget));
+    let setup = Duration::from_secs(4);
+    let remaining = timeout.delay().remaining() - setup;
+    timeout.as_mut().set_remaining(remaining);
+
+    let left = timeout.delay().remaining();
+    assert!(left <= budget - setup);
+    assert!(left > budget - setup - Duration::from_secs(1));
+
+    timeout.as_mut().set_remaining(Duration::from_millis(10));
+    assert!(timeout.await.is_err());
+}
+
+#[async_std::test]
+async fn timeout_tighten_keeps_earlier_deadline() {
+    let outer = Duration::from_millis(20);
+    let i = Instant::now();
+    // Only one `Timeout`, and so one `Delay`, wraps the future.
+    let timeout: futures_timer::Timeout<Pending<()>> = pending::<()>()
+        .timeout(Duration::from_secs(10))
+        .tighten(outer);
+    assert!(timeout.delay().deadline() <= Instant::now() + outer);
+    assert!(timeout.await.is_err());
+    assert!(i.elapsed() < Duration::from_secs(10));
+
+    let timeout = pending::<()>().timeout(outer);
+    let deadline = timeout.delay().deadline();
+    let timeout = timeout.tighten(Duration::from_secs(10));
+    assert_eq!(timeout.delay().deadline(), deadline);
+}
+
+#[async_std::test]
+async fn timeout_diagnostic_counts_polls() {
+    let ok = async { 1 }
+        .timeout_diagnostic(Duration::from_secs(10))
+        .await;
+    assert_eq!(ok, Ok(1));
+
+    let err = pending::<()>()
+        .timeout_diagnostic(Duration::from_millis(10))
+        .await
+        .unwrap_err();
+    assert!(err.inner_poll_count() >= 1);
+}
+
+#[test]
+fn timeout_diagnostic_zero_when_never_polled() {
+    let waker = futures::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut timeout = Box::pin(async { 1 }.timeout_diagnostic(Duration::from_millis(10)));
+    std::thread::sleep(Duration::from_millis(50));
+
+    match timeout.as_mut().poll(&mut cx) {
+        Poll::Ready(Err(err)) => assert_eq!(err.inner_poll_count(), 0),
+        other => panic!("expected the deadline to have passed, got {:?}", other),
+    }
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "futures-core")]
+
+use std::time::Duration;
+
+use futures::future::{pending, ready, FusedFuture};
+use futures::select;
+use futures_timer::{Delay, FutureExt};
+
+#[async_std::test]
+async fn select_skips_fired_delay() {
+    let mut short = Delay::new(Duration::from_millis(10));
+    let mut long = Delay::new(Duration::from_millis(100));
+    let mut fired = 0;
+    loop {
+        select! {
+            () = short => fired += 1,
+            () = long => break,
+        }
+    }
+    assert_eq!(fired, 1);
+    assert!(short.is_terminated());
+    assert!(long.is_terminated());
+}
+
+#[async_std::test]
+async fn reset_unterminates_delay() {
+    let mut delay = Delay::new(Duration::from_millis(10));
+    assert!(!delay.is_terminated());
+    (&mut delay).await;
+    assert!(delay.is_terminated());
+    delay.reset(Duration::from_millis(10));
+    assert!(!delay.is_terminated());
+    (&mut delay).await;
+    assert!(delay.is_terminated());
+}
+
+#[async_std::test]
+async fn timeout_terminated() {
+    let mut ok = ready(1).timeout(Duration::from_secs(10));
+    assert!(!ok.is_terminated());
+    assert_eq!((&mut ok).await, Ok(1));
+    assert!(ok.is_terminated());
+
+    let mut elapsed = pending::<()>().timeout(Duration::from_millis(10));
+    assert!((&mut elapsed).await.is_err());
+    assert!(elapsed.is_terminated());
+}
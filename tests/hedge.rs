@@ -0,0 +1,107 @@
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_timer::{hedge, Delay};
+
+#[async_std::test]
+async fn slow_first_attempt_is_hedged() {
+    let started = Arc::new(AtomicUsize::new(0));
+    let i = Instant::now();
+    let result = hedge(
+        || {
+            let n = started.fetch_add(1, SeqCst);
+            async move {
+                let dur = if n == 0 { 10_000 } else { 10 };
+                Delay::new(Duration::from_millis(dur)).await;
+                Ok::<_, ()>(n)
+            }
+        },
+        Duration::from_millis(20),
+        3,
+    )
+    .await;
+    assert_eq!(result, Ok(1));
+    assert_eq!(started.load(SeqCst), 2);
+    assert!(i.elapsed() < Duration::from_secs(10));
+}
+
+#[async_std::test]
+async fn fast_first_attempt_is_not_hedged() {
+    let started = Arc::new(AtomicUsize::new(0));
+    let result = hedge(
+        || {
+            let n = started.fetch_add(1, SeqCst);
+            async move { Ok::<_, ()>(n) }
+        },
+        Duration::from_millis(10),
+        3,
+    )
+    .await;
+    assert_eq!(result, Ok(0));
+    Delay::new(Duration::from_millis(50)).await;
+    assert_eq!(started.load(SeqCst), 1);
+}
+
+#[async_std::test]
+async fn failures_start_next_attempt() {
+    let started = Arc::new(AtomicUsize::new(0));
+    let i = Instant::now();
+    let result = hedge(
+        || {
+            let n = started.fetch_add(1, SeqCst);
+            async move { Err::<(), _>(n) }
+        },
+        Duration::from_secs(10),
+        3,
+    )
+    .await;
+    assert_eq!(result, Err(2));
+    assert_eq!(started.load(SeqCst), 3);
+    assert!(i.elapsed() < Duration::from_secs(10));
+}
+
+#[async_std::test]
+async fn failure_with_attempt_in_flight_starts_next() {
+    let started = Arc::new(AtomicUsize::new(0));
+    let i = Instant::now();
+    let hedge_after = Duration::from_millis(200);
+    let result = hedge(
+        || {
+            let n = started.fetch_add(1, SeqCst);
+            async move {
+                match n {
+                    0 => futures::future::pending().await,
+                    1 => {
+                        Delay::new(Duration::from_millis(10)).await;
+                        Err(n)
+                    }
+                    _ => Ok(n),
+                }
+            }
+        },
+        hedge_after,
+        3,
+    )
+    .await;
+    // The third attempt starts as soon as the second fails, rather than a
+    // full `hedge_after` later.
+    assert_eq!(result, Ok(2));
+    assert!(i.elapsed() < hedge_after * 2);
+}
+
+#[async_std::test]
+async fn nothing_starts_until_polled() {
+    let started = Arc::new(AtomicUsize::new(0));
+    let future = hedge(
+        || {
+            let n = started.fetch_add(1, SeqCst);
+            async move { Ok::<_, ()>(n) }
+        },
+        Duration::from_millis(10),
+        3,
+    );
+    Delay::new(Duration::from_millis(20)).await;
+    assert_eq!(started.load(SeqCst), 0);
+    assert_eq!(future.await, Ok(0));
+}
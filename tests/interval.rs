@@ -0,0 +1,125 @@
+#![cfg(feature = "futures-core")]
+
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use futures_timer::{Delay, Interval};
+
+#[async_std::test]
+async fn ticks() {
+    let period = Duration::from_millis(20);
+    let start = Instant::now();
+    let mut interval = Interval::new_at(start + period, period);
+    for i in 1..=3 {
+        let tick = interval.next().await.unwrap();
+        assert_eq!(tick, start + period * i);
+        assert!(Instant::now() >= tick);
+    }
+}
+
+#[async_std::test]
+async fn bursts_to_catch_up() {
+    let period = Duration::from_millis(20);
+    let start = Instant::now();
+    let mut interval = Interval::new_at(start + period, period);
+    Delay::new(period * 4).await;
+
+    // All of the missed ticks are yielded right away, on their schedule.
+    let caught_up = Instant::now();
+    for i in 1..=4 {
+        assert_eq!(interval.next().await.unwrap(), start + period * i);
+    }
+    assert!(caught_up.elapsed() < period);
+}
+
+#[async_std::test]
+async fn skips_missed_ticks() {
+    let period = Duration::from_millis(20);
+    let mut interval = Interval::new_skipping(period);
+    let first = interval.next().await.unwrap();
+    Delay::new(period * 4).await;
+
+    // The tick which was due when we stalled still fires, but the next one is
+    // back on the original schedule in the future.
+    let late = interval.next().await.unwrap();
+    assert_eq!(late, first + period);
+    let next = interval.next().await.unwrap();
+    assert!(next >= first + period * 5);
+    assert_eq!((next - first).as_nanos() % period.as_nanos(), 0);
+}
+
+#[async_std::test]
+async fn counts_missed_ticks() {
+    let period = Duration::from_millis(50);
+    let mut interval = Interval::new(period).with_miss_count();
+    let (first, missed) = interval.next().await.unwrap();
+    assert_eq!(missed, 0);
+    Delay::new(period * 4 + period / 2).await;
+
+    // The stalled tick is yielded with the count of the ticks skipped after
+    // it, and the stream then continues on the original schedule.
+    let (late, missed) = interval.next().await.unwrap();
+    assert_eq!(late, first + period);
+    assert!(missed >= 3, "{} missed", missed);
+    let (next, _) = interval.next().await.unwrap();
+    assert_eq!(next, late + period * (missed + 1));
+}
+
+#[async_std::test]
+async fn spacing_follows_closure() {
+    let spacings = [10, 30, 20, 5, 15].map(Duration::from_millis);
+    let mut next = spacings.iter().copied();
+    let start = Instant::now();
+    let mut ticks = Interval::with_spacing(move || next.next().unwrap_or(Duration::from_secs(10)));
+
+    let mut prev = ticks.next().await.unwrap();
+    assert!(prev - start >= spacings[0]);
+    for &spacing in &spacings[1..] {
+        let tick = ticks.next().await.unwrap();
+        assert_eq!(tick - prev, spacing);
+        assert!(Instant::now() >= tick);
+        prev = tick;
+    }
+}
+
+#[async_std::test]
+async fn random_spacing_within_range() {
+    let range = Duration::from_millis(5)..Duration::from_millis(15);
+    let mut ticks = Interval::random(range.clone());
+    let mut prev = ticks.next().await.unwrap();
+    for _ in 0..5 {
+        let tick = ticks.next().await.unwrap();
+        assert!(range.contains(&(tick - prev)), "{:?}", tick - prev);
+        prev = tick;
+    }
+}
+
+#[async_std::test]
+async fn cumulative_drift_grows_when_late() {
+    let period = Duration::from_millis(10);
+    let mut interval = Interval::new(period);
+    interval.next().await.unwrap();
+    let mut drift = interval.cumulative_drift();
+
+    // Each cycle takes half a period longer than the period, so every tick is
+    // observed later than the one before.
+    for i in 1..=4 {
+        Delay::new(period * 3 / 2).await;
+        interval.next().await.unwrap();
+        let total = interval.cumulative_drift();
+        assert!(total >= drift + period / 2 * i);
+        drift = total;
+    }
+}
+
+#[test]
+#[should_panic(expected = "spacing must be non-zero")]
+fn zero_spacing_panics() {
+    let _ = Interval::with_spacing(|| Duration::from_secs(0));
+}
+
+#[test]
+#[should_panic(expected = "`range` must not include zero")]
+fn zero_random_spacing_panics() {
+    let _ = Interval::random(Duration::from_secs(0)..Duration::from_millis(10));
+}
@@ -0,0 +1,36 @@
+use std::time::{Duration, Instant};
+
+use futures::future;
+use futures_timer::{join_timeout, Delay};
+
+#[async_std::test]
+async fn partial() {
+    let dur = Duration::from_millis(50);
+    let i = Instant::now();
+    let fast = async {
+        Delay::new(Duration::from_millis(10)).await;
+        1
+    };
+    let (a, b) = join_timeout(fast, future::pending::<()>(), dur).await;
+    assert_eq!(a, Some(1));
+    assert_eq!(b, None);
+    assert!(i.elapsed() >= dur);
+}
+
+#[async_std::test]
+async fn both_complete() {
+    let dur = Duration::from_secs(10);
+    let i = Instant::now();
+    let (a, b) = join_timeout(async { 1 }, async { "b" }, dur).await;
+    assert_eq!(a, Some(1));
+    assert_eq!(b, Some("b"));
+    assert!(i.elapsed() < dur);
+}
+
+#[async_std::test]
+async fn neither_complete() {
+    let dur = Duration::from_millis(10);
+    let (a, b) = join_timeout(future::pending::<u8>(), future::pending::<u8>(), dur).await;
+    assert_eq!(a, None);
+    assert_eq!(b, None);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "metrics")]
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures_timer::{Delay, TimerStats};
+
+// The counters are global, so tests asserting on exact deltas must not run
+// concurrently with tests creating delays. The dev-dependencies need a newer
+// compiler than the library's minimum anyway.
+#[allow(clippy::incompatible_msrv)]
+static SERIAL: Mutex<()> = Mutex::new(());
+
+#[test]
+fn deadline_buckets() {
+    let _serial = SERIAL.lock().unwrap();
+    let stats = TimerStats::global();
+    let before = stats.deadline_distribution();
+
+    let mut short = Delay::new(Duration::from_millis(1));
+    let _medium = Delay::new(Duration::from_millis(50));
+    let _long = Delay::new(Duration::from_secs(60));
+    short.reset(Duration::from_secs(5));
+
+    let after = stats.deadline_distribution();
+    assert_eq!(after.under_10ms - before.under_10ms, 1);
+    assert_eq!(after.under_100ms - before.under_100ms, 1);
+    assert_eq!(after.under_1s - before.under_1s, 0);
+    assert_eq!(after.under_10s - before.under_10s, 1);
+    assert_eq!(after.longer - before.longer, 1);
+}
+
+#[test]
+fn reset_rate() {
+    let _serial = SERIAL.lock().unwrap();
+    let stats = TimerStats::global();
+    let before = stats.resets();
+
+    let mut delay = Delay::new(Duration::from_secs(60));
+    for _ in 0..100 {
+        delay.reset(Duration::from_secs(60));
+    }
+
+    assert!(stats.resets() - before >= 100);
+    assert!(stats.reset_rate() > 0.0);
+}
+
+#[test]
+fn new_after_is_recorded() {
+    let _serial = SERIAL.lock().unwrap();
+    let stats = TimerStats::global();
+    let base = Delay::new(Duration::from_secs(60));
+    let before = stats.deadline_distribution();
+
+    let _after = Delay::new_after(&base, Duration::from_secs(1));
+    let after = stats.deadline_distribution();
+    assert_eq!(after.longer - before.longer, 1);
+}
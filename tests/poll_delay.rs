@@ -0,0 +1,41 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+use futures_timer::poll_delay;
+
+#[async_std::test]
+async fn resolves_with_closure() {
+    let dur = Duration::from_millis(10);
+    let start = Instant::now();
+    let mut polls = 0;
+    let n = poll_delay(dur, |cx, delay| {
+        polls += 1;
+        Pin::new(delay).poll(cx).map(|()| polls)
+    })
+    .await;
+    assert!(n >= 2);
+    assert!(start.elapsed() >= dur);
+}
+
+#[async_std::test]
+async fn reset_from_closure() {
+    let dur = Duration::from_millis(10);
+    let start = Instant::now();
+    let mut resets = 0;
+    poll_delay(dur, |cx, delay| {
+        if Pin::new(&mut *delay).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+        if resets == 2 {
+            return Poll::Ready(());
+        }
+        resets += 1;
+        delay.reset(dur);
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await;
+    assert!(start.elapsed() >= dur * 3);
+}
@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+use futures::executor::block_on;
+use futures_timer::{reset_global, shutdown_global, Delay};
+
+#[test]
+fn respawns_after_reset() {
+    let dur = Duration::from_millis(10);
+    reset_global().unwrap();
+    block_on(Delay::new(dur));
+
+    reset_global().unwrap();
+    block_on(Delay::new(dur));
+    block_on(Delay::try_new(dur).unwrap());
+
+    shutdown_global().unwrap();
+    assert!(Delay::try_new(dur).is_err());
+
+    reset_global().unwrap();
+    block_on(Delay::new(dur));
+}
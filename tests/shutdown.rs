@@ -0,0 +1,17 @@
+use std::panic;
+use std::time::Duration;
+
+use futures::executor::block_on;
+use futures_timer::{shutdown_global, Delay};
+
+#[test]
+fn shutdown() {
+    block_on(Delay::new(Duration::from_millis(10)));
+
+    shutdown_global().unwrap();
+    shutdown_global().unwrap();
+
+    let res = panic::catch_unwind(|| block_on(Delay::new(Duration::from_millis(10))));
+    assert!(res.is_err());
+    assert!(Delay::try_new(Duration::from_millis(10)).is_err());
+}
@@ -0,0 +1,52 @@
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use futures_timer::{sleep, sleep_until, timeout, timeout_pinned};
+
+#[async_std::test]
+async fn sleep_waits() {
+    let i = Instant::now();
+    let dur = Duration::from_millis(50);
+    sleep(dur).await;
+    assert!(i.elapsed() >= dur);
+}
+
+#[async_std::test]
+async fn sleep_until_waits() {
+    let at = Instant::now() + Duration::from_millis(50);
+    sleep_until(at).await;
+    assert!(Instant::now() >= at);
+}
+
+#[async_std::test]
+async fn sleep_can_be_reset() {
+    let i = Instant::now();
+    let dur = Duration::from_millis(50);
+    let mut delay = sleep(Duration::from_secs(10));
+    delay.reset(dur);
+    delay.await;
+    assert!(i.elapsed() < Duration::from_secs(10));
+}
+
+#[async_std::test]
+async fn timeout_fn() {
+    let dur = Duration::from_millis(10);
+    assert_eq!(timeout(dur, async { 1 }).await, Ok(1));
+    assert!(timeout(dur, sleep(Duration::from_secs(10))).await.is_err());
+}
+
+#[async_std::test]
+async fn timeout_pinned_retimes_same_future() {
+    let mut slow = Box::pin(sleep(Duration::from_millis(100)));
+    assert!(timeout_pinned(Duration::from_millis(10), slow.as_mut())
+        .await
+        .is_err());
+
+    // Extending an elapsed timeout keeps driving the same future.
+    let mut timeout = timeout_pinned(Duration::from_millis(10), slow.as_mut());
+    assert!((&mut timeout).await.is_err());
+    Pin::new(&mut timeout).reset(Duration::from_secs(10));
+    assert_eq!(timeout.await, Ok(()));
+
+    assert!(slow.is_elapsed());
+}
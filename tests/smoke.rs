@@ -1,7 +1,15 @@
 use std::error::Error;
+use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::sync::Arc;
+use std::task::Context;
+use std::thread;
 use std::time::{Duration, Instant};
 
+use futures::executor::block_on;
+use futures::task::{waker, ArcWake};
+
 use futures_timer::Delay;
 
 #[async_std::test]
@@ -29,3 +37,111 @@ async fn reset() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
     assert!(i.elapsed() > dur);
     Ok(())
 }
+
+#[async_std::test]
+async fn new_after() {
+    let i = Instant::now();
+    let dur = Duration::from_millis(50);
+    let first = Delay::new(dur);
+    let second = Delay::new_after(&first, dur);
+    second.await;
+    assert!(i.elapsed() > dur * 2);
+}
+
+#[async_std::test]
+async fn new_after_elapsed() {
+    let dur = Duration::from_millis(50);
+    let mut first = Delay::new(dur);
+    Pin::new(&mut first).await;
+
+    // The original deadline has passed, so this fires promptly
+    let i = Instant::now();
+    Delay::new_after(&first, Duration::from_millis(0)).await;
+    assert!(i.elapsed() < dur);
+}
+
+struct Flag(AtomicBool);
+
+impl ArcWake for Flag {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.store(true, SeqCst);
+    }
+}
+
+#[test]
+fn repoll_same_waker() {
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = waker(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    let dur = Duration::from_millis(10);
+    let mut d = Delay::new(dur);
+
+    for _ in 0..100 {
+        assert!(Pin::new(&mut d).poll(&mut cx).is_pending());
+    }
+    thread::sleep(dur * 5);
+    assert!(flag.0.swap(false, SeqCst));
+    assert!(Pin::new(&mut d).poll(&mut cx).is_ready());
+}
+
+#[test]
+fn reset_after_fire_without_poll() {
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = waker(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    let dur = Duration::from_millis(10);
+    let mut d = Delay::new(dur);
+
+    assert!(Pin::new(&mut d).poll(&mut cx).is_pending());
+    thread::sleep(dur * 5);
+    assert!(flag.0.swap(false, SeqCst));
+
+    // Firing consumed the registered waker, so polling with the same waker
+    // after a reset must register it again.
+    d.reset(dur);
+    assert!(Pin::new(&mut d).poll(&mut cx).is_pending());
+    thread::sleep(dur * 5);
+    assert!(flag.0.swap(false, SeqCst));
+    assert!(Pin::new(&mut d).poll(&mut cx).is_ready());
+}
+
+#[test]
+fn try_new_wakes_blocked_thread() {
+    // `block_on` parks this thread until the delay's waker unparks it, so the
+    // helper thread is the only thing which can make progress here.
+    let i = Instant::now();
+    let dur = Duration::from_millis(50);
+    block_on(Delay::try_new(dur).unwrap());
+    assert!(i.elapsed() >= dur);
+    assert!(i.elapsed() < dur * 20);
+}
+
+#[async_std::test]
+async fn new_in_range() {
+    let i = Instant::now();
+    let range = Duration::from_millis(10)..Duration::from_millis(20);
+    Delay::seed_in_range(7);
+    Delay::new_in_range(range.clone()).await;
+    assert!(i.elapsed() >= range.start);
+}
+
+#[async_std::test]
+async fn deadline_accessors() {
+    let dur = Duration::from_millis(50);
+    let before = Instant::now();
+    let mut d = Delay::new(dur);
+    assert!(d.deadline() >= before + dur);
+    assert!(d.remaining() <= dur);
+    assert!(!d.is_elapsed());
+
+    // Checking doesn't interfere with polling
+    Pin::new(&mut d).await;
+    assert!(d.is_elapsed());
+    assert_eq!(d.remaining(), Duration::from_secs(0));
+
+    let before = Instant::now();
+    d.reset(dur);
+    assert!(d.deadline() >= before + dur);
+    assert!(!d.is_elapsed());
+    d.await;
+}
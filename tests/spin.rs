@@ -0,0 +1,17 @@
+use std::time::{Duration, Instant};
+
+use futures_timer::{set_spin_threshold, Delay};
+
+#[async_std::test]
+async fn spinning_still_fires_on_time() {
+    set_spin_threshold(Duration::from_millis(5));
+
+    for &dur in &[Duration::from_micros(50), Duration::from_millis(20)] {
+        let i = Instant::now();
+        Delay::new(dur).await;
+        assert!(i.elapsed() >= dur);
+        assert!(i.elapsed() < dur + Duration::from_secs(1));
+    }
+
+    set_spin_threshold(Duration::from_secs(0));
+}
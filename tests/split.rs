@@ -0,0 +1,39 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use futures_timer::Delay;
+
+#[async_std::test]
+async fn natural_fire() {
+    let i = Instant::now();
+    let dur = Duration::from_millis(50);
+    let (_trigger, waiter) = Delay::new(dur).split();
+    waiter.await;
+    assert!(i.elapsed() > dur);
+}
+
+#[async_std::test]
+async fn external_fire() {
+    let i = Instant::now();
+    let (trigger, waiter) = Delay::new(Duration::from_secs(10)).split();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        trigger.fire();
+    });
+    waiter.await;
+    assert!(i.elapsed() < Duration::from_secs(10));
+}
+
+#[async_std::test]
+async fn fire_races_deadline() {
+    for _ in 0..100 {
+        let (trigger, waiter) = Delay::new(Duration::from_millis(1)).split();
+        let fire = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(1));
+            trigger.fire();
+            trigger.fire();
+        });
+        waiter.await;
+        fire.join().unwrap();
+    }
+}
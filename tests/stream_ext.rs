@@ -0,0 +1,152 @@
+#![cfg(feature = "futures-core")]
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, FusedStream, Stream, StreamExt as _};
+use futures::task::{waker, ArcWake};
+use futures_timer::{Delay, StreamExt};
+
+struct Flag(AtomicBool);
+
+impl ArcWake for Flag {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+// Polls `stream` once, returning the result and whether it woke its task.
+fn poll_once<S: Stream + Unpin>(stream: &mut S) -> (Poll<Option<S::Item>>, bool) {
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = waker(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    let poll = Pin::new(stream).poll_next(&mut cx);
+    (poll, flag.0.load(Ordering::SeqCst))
+}
+
+// Yields each item after waiting for its paired duration.
+fn spaced(items: Vec<(u32, u64)>) -> impl futures::Stream<Item = u32> {
+    stream::iter(items).then(|(item, ms)| async move {
+        Delay::new(Duration::from_millis(ms)).await;
+        item
+    })
+}
+
+#[async_std::test]
+async fn throttle_drops_burst() {
+    let items = stream::iter(0..10).throttle(Duration::from_secs(10));
+    assert_eq!(items.collect::<Vec<_>>().await, [0]);
+}
+
+#[async_std::test]
+async fn throttle_reopens_window() {
+    let items = spaced(vec![(1, 0), (2, 0), (3, 100), (4, 0)]).throttle(Duration::from_millis(30));
+    assert_eq!(items.collect::<Vec<_>>().await, [1, 3]);
+}
+
+#[test]
+fn throttle_yields_while_dropping_ready_items() {
+    let mut items = stream::repeat(1).throttle(Duration::from_secs(10));
+    assert_eq!(poll_once(&mut items).0, Poll::Ready(Some(1)));
+
+    let start = Instant::now();
+    assert_eq!(poll_once(&mut items), (Poll::Pending, true));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[async_std::test]
+async fn debounce_emits_last_of_burst() {
+    let items = spaced(vec![(1, 0), (2, 0), (3, 100), (4, 0)]).debounce(Duration::from_millis(30));
+    assert_eq!(items.collect::<Vec<_>>().await, [2, 4]);
+}
+
+#[async_std::test]
+async fn debounce_flushes_on_end() {
+    let items = stream::iter(0..10).debounce(Duration::from_secs(10));
+    assert_eq!(items.collect::<Vec<_>>().await, [9]);
+}
+
+#[test]
+fn debounce_yields_on_endless_ready_stream() {
+    let mut items = stream::iter(0..).debounce(Duration::from_millis(10));
+    let start = Instant::now();
+    for _ in 0..10 {
+        assert_eq!(poll_once(&mut items), (Poll::Pending, true));
+    }
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[async_std::test]
+async fn empty_streams() {
+    let dur = Duration::from_millis(10);
+    assert!(stream::empty::<()>()
+        .throttle(dur)
+        .collect::<Vec<_>>()
+        .await
+        .is_empty());
+    assert!(stream::empty::<()>()
+        .debounce(dur)
+        .collect::<Vec<_>>()
+        .await
+        .is_empty());
+}
+
+#[async_std::test]
+async fn windowed_groups_by_time() {
+    let items = spaced(vec![(1, 0), (2, 0), (3, 150)]).windowed(Duration::from_millis(100));
+    assert_eq!(items.collect::<Vec<_>>().await, [vec![1, 2], vec![3]]);
+}
+
+#[async_std::test]
+async fn windowed_empty_windows() {
+    let window = Duration::from_millis(100);
+    let items = spaced(vec![(1, 0), (2, 250)]).windowed(window);
+    assert_eq!(items.collect::<Vec<_>>().await, [vec![1], vec![2]]);
+
+    let items = spaced(vec![(1, 0), (2, 250)])
+        .windowed(window)
+        .with_empty_windows();
+    assert_eq!(items.collect::<Vec<_>>().await, [vec![1], vec![], vec![2]]);
+}
+
+#[async_std::test]
+async fn windowed_flushes_on_end() {
+    let items = stream::iter(0..3).windowed(Duration::from_secs(10));
+    assert_eq!(items.collect::<Vec<_>>().await, [vec![0, 1, 2]]);
+
+    let items = stream::empty::<()>().windowed(Duration::from_secs(10));
+    assert!(items.collect::<Vec<_>>().await.is_empty());
+}
+
+#[async_std::test]
+async fn take_until_delay_truncates() {
+    let items = spaced(vec![(1, 0), (2, 0), (3, 200)]).take_until_delay(Duration::from_millis(100));
+    assert_eq!(items.collect::<Vec<_>>().await, [1, 2]);
+
+    let mut items = stream::pending::<()>().take_until_delay(Duration::from_millis(10));
+    assert!(!items.is_terminated());
+    assert_eq!(items.next().await, None);
+    assert!(items.is_terminated());
+}
+
+#[test]
+fn take_until_delay_past_deadline_ends_right_away() {
+    let mut items = stream::repeat(1).take_until_delay_at(Instant::now());
+    assert_eq!(poll_once(&mut items).0, Poll::Ready(None));
+
+    let mut items = stream::repeat(1).take_until_delay(Duration::from_secs(0));
+    assert_eq!(poll_once(&mut items).0, Poll::Ready(None));
+}
+
+#[async_std::test]
+async fn with_intervals_measures_gaps() {
+    let items = spaced(vec![(1, 20), (2, 0), (3, 40)]).with_intervals();
+    let items = items.collect::<Vec<_>>().await;
+    assert_eq!(items.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [1, 2, 3]);
+    assert!(items[0].1 >= Duration::from_millis(20));
+    assert!(items[1].1 < Duration::from_millis(20));
+    assert!(items[2].1 >= Duration::from_millis(40));
+}
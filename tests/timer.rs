@@ -0,0 +1,92 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::future::{pending, ready};
+use futures::task::noop_waker;
+use futures_timer::{Delay, FutureExt, Timer};
+
+fn poll<F: Future + Unpin>(f: &mut F) -> Poll<F::Output> {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    Pin::new(f).poll(&mut cx)
+}
+
+#[test]
+fn delay_fires_when_timer_advances() {
+    let mut timer = Timer::new();
+    let start = Instant::now();
+    let mut delay = Delay::new_at_handle(start + Duration::from_secs(3600), timer.handle());
+
+    assert!(poll(&mut delay).is_pending());
+    assert!(poll(&mut timer).is_pending());
+    timer.advance_to(start + Duration::from_secs(3599));
+    assert!(poll(&mut delay).is_pending());
+
+    timer.advance_to(start + Duration::from_secs(3600));
+    assert!(poll(&mut delay).is_ready());
+}
+
+#[test]
+fn delay_ignores_the_default_timer() {
+    let timer = Timer::new();
+    let mut delay = Delay::new_handle(Duration::from_millis(0), timer.handle());
+    std::thread::sleep(Duration::from_millis(10));
+    assert!(poll(&mut delay).is_pending());
+}
+
+#[test]
+fn timeout_with_follows_timer() {
+    let mut timer = Timer::new();
+    let start = Instant::now();
+    let dur = Duration::from_secs(3600);
+
+    let mut ok = ready(1).timeout_with(dur, timer.handle());
+    assert_eq!(poll(&mut ok), Poll::Ready(Ok(1)));
+
+    let mut slow = pending::<()>().timeout_with(dur, timer.handle());
+    assert!(poll(&mut slow).is_pending());
+    assert!(poll(&mut timer).is_pending());
+    timer.advance_to(start + dur * 2);
+    assert!(matches!(poll(&mut slow), Poll::Ready(Err(_))));
+}
+
+#[test]
+fn timeout_with_waits_for_timer_to_advance() {
+    let mut timer = Timer::new();
+    let dur = Duration::from_millis(5);
+    let mut slow = pending::<()>().timeout_with(dur, timer.handle());
+    assert!(poll(&mut slow).is_pending());
+
+    std::thread::sleep(dur * 4);
+    assert!(poll(&mut slow).is_pending());
+
+    assert!(poll(&mut timer).is_pending());
+    timer.advance_to(Instant::now());
+    assert!(matches!(poll(&mut slow), Poll::Ready(Err(_))));
+}
+
+#[test]
+fn timeout_default_follows_handle_default() {
+    let mut timer = Timer::new();
+    let start = Instant::now();
+    let handle = timer.handle();
+    assert_eq!(handle.default_timeout(), None);
+
+    handle.set_default_timeout(Duration::from_secs(10));
+    let mut short = pending::<()>().timeout_default(timer.handle());
+    handle.set_default_timeout(Duration::from_secs(3600));
+    assert_eq!(
+        timer.handle().default_timeout(),
+        Some(Duration::from_secs(3600))
+    );
+    let mut long = pending::<()>().timeout_default(timer.handle());
+
+    assert!(poll(&mut short).is_pending());
+    assert!(poll(&mut long).is_pending());
+    assert!(poll(&mut timer).is_pending());
+    timer.advance_to(start + Duration::from_secs(60));
+    assert!(matches!(poll(&mut short), Poll::Ready(Err(_))));
+    assert!(poll(&mut long).is_pending());
+}
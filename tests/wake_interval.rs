@@ -0,0 +1,30 @@
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use futures_timer::{set_min_wake_interval, Delay};
+
+#[async_std::test]
+async fn coalesces_short_delays() {
+    let interval = Duration::from_millis(50);
+    set_min_wake_interval(interval);
+
+    #[cfg(feature = "metrics")]
+    let before = futures_timer::TimerStats::global().wakeups();
+
+    let start = Instant::now();
+    let delays = (1..=100).map(|i| Delay::new(Duration::from_millis(i * 2)));
+    join_all(delays).await;
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(200));
+
+    // Every delay has fired, but the helper thread didn't wake up more than
+    // once per interval to fire them.
+    #[cfg(feature = "metrics")]
+    {
+        let wakeups = futures_timer::TimerStats::global().wakeups() - before;
+        let cap = (elapsed.as_millis() / interval.as_millis()) as usize + 2;
+        assert!(wakeups <= cap, "{} wakeups, cap {}", wakeups, cap);
+    }
+
+    set_min_wake_interval(Duration::from_secs(0));
+}